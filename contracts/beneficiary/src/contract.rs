@@ -20,6 +20,35 @@ impl BeneficiaryContract {
         Ok(())
     }
 
+    /// Pause every state-changing call. Reads keep working.
+    pub fn pause(env: Env) -> Result<(), BeneficiaryError> {
+        let admin = storage::get_admin(&env);
+        admin.require_auth();
+        storage::set_paused(&env, true);
+
+        env.events()
+            .publish((symbol_short!("benef"), symbol_short!("paused")), admin);
+
+        Ok(())
+    }
+
+    /// Lift a pause set by `pause`.
+    pub fn unpause(env: Env) -> Result<(), BeneficiaryError> {
+        let admin = storage::get_admin(&env);
+        admin.require_auth();
+        storage::set_paused(&env, false);
+
+        env.events()
+            .publish((symbol_short!("benef"), symbol_short!("unpaused")), admin);
+
+        Ok(())
+    }
+
+    /// Whether the contract is paused.
+    pub fn is_paused(env: Env) -> bool {
+        storage::is_paused(&env)
+    }
+
     /// Set the vault contract address for cross-contract calls.
     pub fn set_vault_contract(env: Env, vault_contract: Address) -> Result<(), BeneficiaryError> {
         let admin = storage::get_admin(&env);
@@ -34,6 +63,8 @@ impl BeneficiaryContract {
         vault_id: u64,
         beneficiaries: Vec<Beneficiary>,
     ) -> Result<(), BeneficiaryError> {
+        ensure_not_paused(&env)?;
+
         let admin = storage::get_admin(&env);
         admin.require_auth();
 
@@ -78,6 +109,8 @@ impl BeneficiaryContract {
 
     /// Record that a beneficiary has claimed.
     pub fn record_claim(env: Env, vault_id: u64, claimer: Address) -> Result<u32, BeneficiaryError> {
+        ensure_not_paused(&env)?;
+
         let admin = storage::get_admin(&env);
         admin.require_auth();
 
@@ -109,3 +142,11 @@ impl BeneficiaryContract {
         Ok(percentage)
     }
 }
+
+/// Reject state-changing calls while the admin has paused the contract.
+fn ensure_not_paused(env: &Env) -> Result<(), BeneficiaryError> {
+    if storage::is_paused(env) {
+        return Err(BeneficiaryError::Paused);
+    }
+    Ok(())
+}
@@ -13,4 +13,5 @@ pub enum BeneficiaryError {
     NotInitialized = 8,
     PercentageSumInvalid = 9,
     NoBeneficiaries = 10,
+    Paused = 11,
 }
@@ -10,6 +10,7 @@ pub enum DataKey {
     VaultBeneficiaries(u64),
     ClaimRecord(u64, Address),
     Initialized,
+    Paused,
 }
 
 pub fn is_initialized(env: &Env) -> bool {
@@ -20,6 +21,14 @@ pub fn set_initialized(env: &Env) {
     env.storage().instance().set(&DataKey::Initialized, &true);
 }
 
+pub fn is_paused(env: &Env) -> bool {
+    env.storage().instance().get(&DataKey::Paused).unwrap_or(false)
+}
+
+pub fn set_paused(env: &Env, paused: bool) {
+    env.storage().instance().set(&DataKey::Paused, &paused);
+}
+
 pub fn get_admin(env: &Env) -> Address {
     env.storage().instance().get(&DataKey::Admin).unwrap()
 }
@@ -149,8 +149,8 @@ fn test_can_claim() {
     beneficiaries.push_back(b1);
     client.set_beneficiaries(&0u64, &beneficiaries);
 
-    assert_eq!(client.can_claim(&0u64, &claimer), true);
-    assert_eq!(client.can_claim(&0u64, &non_beneficiary), false);
+    assert!(client.can_claim(&0u64, &claimer));
+    assert!(!client.can_claim(&0u64, &non_beneficiary));
 }
 
 // ---------------------------------------------------------------------------
@@ -189,7 +189,7 @@ fn test_record_claim() {
     assert_eq!(percentage, 7000);
 
     // After claiming, can_claim should return false
-    assert_eq!(client.can_claim(&0u64, &claimer), false);
+    assert!(!client.can_claim(&0u64, &claimer));
 }
 
 // ---------------------------------------------------------------------------
@@ -2,7 +2,14 @@ use soroban_sdk::{contract, contractimpl, symbol_short, Address, Bytes, BytesN,
 
 use crate::errors::DocumentError;
 use crate::storage;
-use crate::types::{DocId, DocumentAccess, DocumentInfo, DocumentProof, DocumentType};
+use crate::types::{
+    AccessRecord, BatchId, BatchInfo, DocId, DocumentAccess, DocumentInfo, DocumentProof, DocumentRelease, DocumentType,
+    Endorsement,
+};
+use crate::vault::{VaultClient, VaultStatus};
+
+const MAX_PAGE_SIZE: u32 = 100;
+const MAX_ACCESS_RECORDS: u32 = 100;
 
 #[contract]
 pub struct DocumentRegistryContract;
@@ -20,6 +27,114 @@ impl DocumentRegistryContract {
         Ok(())
     }
 
+    /// Pause every state-changing call. Reads keep working.
+    pub fn pause(env: Env) -> Result<(), DocumentError> {
+        let admin = storage::get_admin(&env);
+        admin.require_auth();
+        storage::set_paused(&env, true);
+
+        env.events()
+            .publish((symbol_short!("doc"), symbol_short!("paused")), admin);
+
+        Ok(())
+    }
+
+    /// Lift a pause set by `pause`.
+    pub fn unpause(env: Env) -> Result<(), DocumentError> {
+        let admin = storage::get_admin(&env);
+        admin.require_auth();
+        storage::set_paused(&env, false);
+
+        env.events()
+            .publish((symbol_short!("doc"), symbol_short!("unpaused")), admin);
+
+        Ok(())
+    }
+
+    /// Whether the contract is paused.
+    pub fn is_paused(env: Env) -> bool {
+        storage::is_paused(&env)
+    }
+
+    /// Set the vault contract address for cross-contract calls.
+    pub fn set_vault_contract(env: Env, vault_contract: Address) -> Result<(), DocumentError> {
+        let admin = storage::get_admin(&env);
+        admin.require_auth();
+        storage::set_vault_contract(&env, &vault_contract);
+        Ok(())
+    }
+
+    /// Approve a notary allowed to endorse documents.
+    pub fn add_notary(env: Env, notary: Address) -> Result<(), DocumentError> {
+        let admin = storage::get_admin(&env);
+        admin.require_auth();
+        storage::set_notary(&env, &notary);
+
+        env.events()
+            .publish((symbol_short!("doc"), symbol_short!("notary")), (&notary, true));
+
+        Ok(())
+    }
+
+    /// Remove a notary. Existing endorsements are kept.
+    pub fn remove_notary(env: Env, notary: Address) -> Result<(), DocumentError> {
+        let admin = storage::get_admin(&env);
+        admin.require_auth();
+        storage::remove_notary(&env, &notary);
+
+        env.events()
+            .publish((symbol_short!("doc"), symbol_short!("notary")), (&notary, false));
+
+        Ok(())
+    }
+
+    /// Check whether an address is an approved notary.
+    pub fn is_notary(env: Env, notary: Address) -> bool {
+        storage::is_notary(&env, &notary)
+    }
+
+    /// Endorse a document hash with a statement. Only callable by approved notaries.
+    pub fn endorse(
+        env: Env,
+        notary: Address,
+        doc_id: DocId,
+        statement_hash: BytesN<32>,
+    ) -> Result<(), DocumentError> {
+        ensure_not_paused(&env)?;
+
+        notary.require_auth();
+
+        if !storage::is_notary(&env, &notary) {
+            return Err(DocumentError::NotANotary);
+        }
+
+        let doc = storage::get_document(&env, doc_id)
+            .ok_or(DocumentError::DocumentNotFound)?;
+
+        if doc.revoked {
+            return Err(DocumentError::DocumentRevoked);
+        }
+
+        let mut endorsements = storage::get_endorsements(&env, doc_id);
+        for e in endorsements.iter() {
+            if e.notary == notary && e.statement_hash == statement_hash {
+                return Err(DocumentError::AlreadyEndorsed);
+            }
+        }
+
+        endorsements.push_back(Endorsement {
+            notary: notary.clone(),
+            statement_hash,
+            timestamp: env.ledger().timestamp(),
+        });
+        storage::set_endorsements(&env, doc_id, &endorsements);
+
+        env.events()
+            .publish((symbol_short!("doc"), symbol_short!("endorse")), (doc_id, &notary));
+
+        Ok(())
+    }
+
     /// Register a new document. Returns DocId.
     pub fn register_document(
         env: Env,
@@ -29,6 +144,8 @@ impl DocumentRegistryContract {
         doc_type: DocumentType,
         is_encrypted: bool,
     ) -> Result<DocId, DocumentError> {
+        ensure_not_paused(&env)?;
+
         owner.require_auth();
 
         let doc_id = storage::get_doc_count(&env);
@@ -42,10 +159,17 @@ impl DocumentRegistryContract {
             registered_at: env.ledger().timestamp(),
             vault_id: 0,
             linked: false,
+            version: 1,
+            previous_id: None,
+            superseded_by: None,
+            revoked: false,
+            release_after_trigger_secs: None,
+            release_at: None,
         };
 
         storage::set_document(&env, doc_id, &doc);
         storage::set_doc_count(&env, doc_id + 1);
+        storage::add_owner_document(&env, &doc.owner, doc_id);
 
         env.events()
             .publish((symbol_short!("doc"), symbol_short!("register")), doc_id);
@@ -53,13 +177,172 @@ impl DocumentRegistryContract {
         Ok(doc_id)
     }
 
-    /// Link a document to a vault.
+    /// Anchor a bundle of `count` files by their Merkle root. Leaves are
+    /// `sha256(0x00 || file_hash)`, parents `sha256(0x01 || min || max)`, and
+    /// an odd node at any level is paired with itself, so every proof has
+    /// exactly `ceil(log2(count))` steps. Returns BatchId.
+    pub fn register_batch(
+        env: Env,
+        owner: Address,
+        merkle_root: BytesN<32>,
+        count: u32,
+        doc_type: DocumentType,
+    ) -> Result<BatchId, DocumentError> {
+        ensure_not_paused(&env)?;
+
+        owner.require_auth();
+
+        if count == 0 {
+            return Err(DocumentError::EmptyBatch);
+        }
+
+        let batch_id = storage::get_batch_count(&env);
+        let batch = BatchInfo {
+            id: batch_id,
+            owner,
+            merkle_root,
+            count,
+            doc_type,
+            registered_at: env.ledger().timestamp(),
+        };
+
+        storage::set_batch(&env, batch_id, &batch);
+        storage::set_batch_count(&env, batch_id + 1);
+
+        env.events()
+            .publish((symbol_short!("doc"), symbol_short!("batch")), (batch_id, count));
+
+        Ok(batch_id)
+    }
+
+    /// Get batch info.
+    pub fn get_batch(env: Env, batch_id: BatchId) -> Result<BatchInfo, DocumentError> {
+        storage::get_batch(&env, batch_id).ok_or(DocumentError::BatchNotFound)
+    }
+
+    /// Check that the file `leaf_hash` is part of a batch. The proof must be
+    /// exactly as deep as the batch's tree, so an internal node can never
+    /// pass for a file.
+    pub fn verify_inclusion(
+        env: Env,
+        batch_id: BatchId,
+        leaf_hash: BytesN<32>,
+        proof: Vec<BytesN<32>>,
+    ) -> Result<bool, DocumentError> {
+        let batch = storage::get_batch(&env, batch_id)
+            .ok_or(DocumentError::BatchNotFound)?;
+
+        if proof.len() != tree_depth(batch.count) {
+            return Ok(false);
+        }
+
+        let mut node = hash_leaf(&env, &leaf_hash);
+        for sibling in proof.iter() {
+            node = hash_pair(&env, &node, &sibling);
+        }
+
+        Ok(node == batch.merkle_root)
+    }
+
+    /// Register a new version of a document. The new version inherits the
+    /// owner, type and vault link; the old one is marked superseded.
+    pub fn supersede_document(
+        env: Env,
+        old_doc_id: DocId,
+        new_cid: String,
+        new_hash: BytesN<32>,
+    ) -> Result<DocId, DocumentError> {
+        ensure_not_paused(&env)?;
+
+        let mut old = storage::get_document(&env, old_doc_id)
+            .ok_or(DocumentError::DocumentNotFound)?;
+
+        old.owner.require_auth();
+
+        if old.revoked {
+            return Err(DocumentError::DocumentRevoked);
+        }
+
+        if old.superseded_by.is_some() {
+            return Err(DocumentError::AlreadySuperseded);
+        }
+
+        let doc_id = storage::get_doc_count(&env);
+        let doc = DocumentInfo {
+            id: doc_id,
+            owner: old.owner.clone(),
+            ipfs_cid: new_cid,
+            doc_hash: new_hash,
+            doc_type: old.doc_type.clone(),
+            is_encrypted: old.is_encrypted,
+            registered_at: env.ledger().timestamp(),
+            vault_id: old.vault_id,
+            linked: old.linked,
+            version: old.version + 1,
+            previous_id: Some(old_doc_id),
+            superseded_by: None,
+            revoked: false,
+            release_after_trigger_secs: old.release_after_trigger_secs,
+            release_at: old.release_at,
+        };
+
+        old.superseded_by = Some(doc_id);
+        storage::set_document(&env, old_doc_id, &old);
+        storage::set_document(&env, doc_id, &doc);
+        storage::set_doc_count(&env, doc_id + 1);
+        storage::add_owner_document(&env, &doc.owner, doc_id);
+        if doc.linked {
+            storage::add_vault_document(&env, doc.vault_id, doc_id);
+        }
+
+        env.events()
+            .publish((symbol_short!("doc"), symbol_short!("supersede")), (old_doc_id, doc_id));
+
+        Ok(doc_id)
+    }
+
+    /// Get every version of a document, oldest first.
+    pub fn get_document_history(env: Env, doc_id: DocId) -> Result<Vec<DocumentInfo>, DocumentError> {
+        let mut doc = storage::get_document(&env, doc_id)
+            .ok_or(DocumentError::DocumentNotFound)?;
+
+        while let Some(next_id) = doc.superseded_by {
+            doc = storage::get_document(&env, next_id).unwrap();
+        }
+
+        let mut history = Vec::new(&env);
+        loop {
+            let previous_id = doc.previous_id;
+            history.push_front(doc);
+            match previous_id {
+                Some(id) => doc = storage::get_document(&env, id).unwrap(),
+                None => break,
+            }
+        }
+
+        Ok(history)
+    }
+
+    /// Link a document to a vault. The vault must exist, be open and be
+    /// owned by the document's owner.
     pub fn link_to_vault(env: Env, doc_id: DocId, vault_id: u64) -> Result<(), DocumentError> {
+        ensure_not_paused(&env)?;
+
         let mut doc = storage::get_document(&env, doc_id)
             .ok_or(DocumentError::DocumentNotFound)?;
 
         doc.owner.require_auth();
 
+        if doc.revoked {
+            return Err(DocumentError::DocumentRevoked);
+        }
+
+        if doc.linked {
+            return Err(DocumentError::AlreadyLinked);
+        }
+
+        ensure_vault_owned_by(&env, vault_id, &doc.owner)?;
+
         doc.vault_id = vault_id;
         doc.linked = true;
         storage::set_document(&env, doc_id, &doc);
@@ -68,6 +351,157 @@ impl DocumentRegistryContract {
         Ok(())
     }
 
+    /// Hand every document `old_owner` has linked to `vault_id` over to
+    /// `new_owner`. Only callable by the vault contract, as part of a vault
+    /// ownership transfer.
+    pub fn transfer_vault_documents(
+        env: Env,
+        vault_id: u64,
+        old_owner: Address,
+        new_owner: Address,
+    ) -> Result<u32, DocumentError> {
+        ensure_not_paused(&env)?;
+
+        let vault_contract = storage::get_vault_contract(&env)
+            .ok_or(DocumentError::VaultContractNotSet)?;
+        vault_contract.require_auth();
+
+        let mut moved = 0;
+        for doc_id in storage::get_vault_documents(&env, vault_id).iter() {
+            let mut doc = match storage::get_document(&env, doc_id) {
+                Some(doc) if doc.owner == old_owner => doc,
+                _ => continue,
+            };
+            doc.owner = new_owner.clone();
+            storage::set_document(&env, doc_id, &doc);
+            storage::remove_owner_document(&env, &old_owner, doc_id);
+            storage::add_owner_document(&env, &new_owner, doc_id);
+            moved += 1;
+        }
+
+        env.events().publish(
+            (symbol_short!("doc"), symbol_short!("owner")),
+            (vault_id, old_owner, new_owner, moved),
+        );
+
+        Ok(moved)
+    }
+
+    /// Unlink a document from its vault. Blocked once the vault is Triggered.
+    pub fn unlink_from_vault(env: Env, doc_id: DocId) -> Result<(), DocumentError> {
+        ensure_not_paused(&env)?;
+
+        let mut doc = storage::get_document(&env, doc_id)
+            .ok_or(DocumentError::DocumentNotFound)?;
+
+        doc.owner.require_auth();
+
+        if !doc.linked {
+            return Err(DocumentError::NotLinked);
+        }
+
+        ensure_vault_not_triggered(&env, doc.vault_id)?;
+
+        storage::remove_vault_document(&env, doc.vault_id, doc_id);
+        doc.vault_id = 0;
+        doc.linked = false;
+        storage::set_document(&env, doc_id, &doc);
+
+        env.events()
+            .publish((symbol_short!("doc"), symbol_short!("unlink")), doc_id);
+
+        Ok(())
+    }
+
+    /// Revoke a document: tombstone the entry, unlink it and delete every
+    /// stored encrypted key. Blocked once the linked vault is Triggered.
+    pub fn revoke_document(env: Env, doc_id: DocId) -> Result<(), DocumentError> {
+        ensure_not_paused(&env)?;
+
+        let mut doc = storage::get_document(&env, doc_id)
+            .ok_or(DocumentError::DocumentNotFound)?;
+
+        doc.owner.require_auth();
+
+        if doc.revoked {
+            return Err(DocumentError::DocumentRevoked);
+        }
+
+        if doc.linked {
+            ensure_vault_not_triggered(&env, doc.vault_id)?;
+            storage::remove_vault_document(&env, doc.vault_id, doc_id);
+        }
+
+        storage::remove_encrypted_keys(&env, doc_id);
+        doc.revoked = true;
+        doc.linked = false;
+        storage::set_document(&env, doc_id, &doc);
+
+        env.events()
+            .publish((symbol_short!("doc"), symbol_short!("revoke")), doc_id);
+
+        Ok(())
+    }
+
+    /// Delay beneficiary access until `release_after_trigger_secs` after the
+    /// vault triggers and/or an absolute `release_at`; the later one wins.
+    pub fn set_release_schedule(
+        env: Env,
+        doc_id: DocId,
+        release_after_trigger_secs: Option<u64>,
+        release_at: Option<u64>,
+    ) -> Result<(), DocumentError> {
+        ensure_not_paused(&env)?;
+
+        let mut doc = storage::get_document(&env, doc_id)
+            .ok_or(DocumentError::DocumentNotFound)?;
+
+        doc.owner.require_auth();
+
+        if doc.revoked {
+            return Err(DocumentError::DocumentRevoked);
+        }
+
+        if doc.linked {
+            ensure_vault_not_triggered(&env, doc.vault_id)?;
+        }
+
+        doc.release_after_trigger_secs = release_after_trigger_secs;
+        doc.release_at = release_at;
+        storage::set_document(&env, doc_id, &doc);
+
+        env.events()
+            .publish((symbol_short!("doc"), symbol_short!("schedule")), doc_id);
+
+        Ok(())
+    }
+
+    /// Get the time at which a document unlocks for beneficiaries. `None` means
+    /// no schedule, or a trigger-relative schedule whose vault has not triggered.
+    pub fn get_unlock_time(env: Env, doc_id: DocId) -> Result<Option<u64>, DocumentError> {
+        let doc = storage::get_document(&env, doc_id)
+            .ok_or(DocumentError::DocumentNotFound)?;
+        Ok(release_of(&env, &doc).unlocks_at)
+    }
+
+    /// List the scheduled releases of a vault's current documents that have
+    /// not unlocked yet.
+    pub fn get_upcoming_releases(env: Env, vault_id: u64) -> Vec<DocumentRelease> {
+        let now = env.ledger().timestamp();
+        let mut releases = Vec::new(&env);
+        for doc_id in Self::get_vault_documents(env.clone(), vault_id).iter() {
+            let doc = storage::get_document(&env, doc_id).unwrap();
+            if doc.release_after_trigger_secs.is_none() && doc.release_at.is_none() {
+                continue;
+            }
+            let release = release_of(&env, &doc);
+            if release.unlocks_at.is_none_or(|unlocks_at| unlocks_at > now) {
+                releases.push_back(release);
+            }
+        }
+        releases
+    }
+
     /// Store an encrypted AES key for a beneficiary.
     pub fn store_encrypted_key(
         env: Env,
@@ -75,9 +509,14 @@ impl DocumentRegistryContract {
         beneficiary: Address,
         encrypted_key: Bytes,
     ) -> Result<(), DocumentError> {
+        ensure_not_paused(&env)?;
+
         let doc = storage::get_document(&env, doc_id)
             .ok_or(DocumentError::DocumentNotFound)?;
         doc.owner.require_auth();
+        if doc.revoked {
+            return Err(DocumentError::DocumentRevoked);
+        }
         storage::set_encrypted_key(&env, doc_id, &beneficiary, &encrypted_key);
         Ok(())
     }
@@ -88,13 +527,19 @@ impl DocumentRegistryContract {
         doc_id: DocId,
         beneficiary: Address,
     ) -> Result<DocumentAccess, DocumentError> {
+        ensure_not_paused(&env)?;
+
         let admin = storage::get_admin(&env);
         admin.require_auth();
 
         let doc = storage::get_document(&env, doc_id)
             .ok_or(DocumentError::DocumentNotFound)?;
 
-        storage::grant_access(&env, doc_id, &beneficiary);
+        if doc.revoked {
+            return Err(DocumentError::DocumentRevoked);
+        }
+
+        record_access(&env, doc_id, &beneficiary);
 
         let encrypted_key = storage::get_encrypted_key(&env, doc_id, &beneficiary)
             .unwrap_or(Bytes::new(&env));
@@ -107,26 +552,288 @@ impl DocumentRegistryContract {
         })
     }
 
+    /// Retrieve a document's access data as a beneficiary. Only succeeds once the
+    /// linked vault is Triggered or Distributed and the caller is one of its
+    /// beneficiaries; access is recorded without admin involvement.
+    pub fn get_document_access(
+        env: Env,
+        doc_id: DocId,
+        beneficiary: Address,
+    ) -> Result<DocumentAccess, DocumentError> {
+        ensure_not_paused(&env)?;
+
+        beneficiary.require_auth();
+        beneficiary_access(&env, doc_id, &beneficiary, None)
+    }
+
+    /// Like `get_document_access`, for a beneficiary the vault only holds as a
+    /// hashed commitment: the caller reveals the salt behind it.
+    pub fn get_document_access_committed(
+        env: Env,
+        doc_id: DocId,
+        beneficiary: Address,
+        salt: BytesN<32>,
+    ) -> Result<DocumentAccess, DocumentError> {
+        ensure_not_paused(&env)?;
+
+        beneficiary.require_auth();
+        beneficiary_access(&env, doc_id, &beneficiary, Some(&salt))
+    }
+
+    /// Get the on-chain access log for a document, oldest entry first.
+    pub fn get_access_log(env: Env, doc_id: DocId) -> Vec<AccessRecord> {
+        storage::get_access_log(&env, doc_id)
+    }
+
+    /// Check whether a beneficiary has been granted access to a document.
+    pub fn has_access(env: Env, doc_id: DocId, beneficiary: Address) -> bool {
+        storage::is_access_granted(&env, doc_id, &beneficiary)
+    }
+
     /// Verify document existence and integrity.
     pub fn verify_document(env: Env, doc_id: DocId) -> Result<DocumentProof, DocumentError> {
         let doc = storage::get_document(&env, doc_id)
             .ok_or(DocumentError::DocumentNotFound)?;
 
         Ok(DocumentProof {
-            exists: true,
+            exists: !doc.revoked,
             doc_hash: doc.doc_hash,
             registered_at: doc.registered_at,
             ipfs_cid: doc.ipfs_cid,
+            version: doc.version,
+            is_latest: doc.superseded_by.is_none(),
+            endorsements: storage::get_endorsements(&env, doc_id),
         })
     }
 
-    /// Get all document IDs for a vault.
+    /// Get the current (non-superseded) document IDs for a vault.
     pub fn get_vault_documents(env: Env, vault_id: u64) -> Vec<DocId> {
+        let mut current = Vec::new(&env);
+        for doc_id in storage::get_vault_documents(&env, vault_id).iter() {
+            if let Some(doc) = storage::get_document(&env, doc_id) {
+                if doc.superseded_by.is_none() {
+                    current.push_back(doc_id);
+                }
+            }
+        }
+        current
+    }
+
+    /// Get all document IDs for a vault, including superseded versions.
+    pub fn get_all_vault_documents(env: Env, vault_id: u64) -> Vec<DocId> {
         storage::get_vault_documents(&env, vault_id)
     }
 
+    /// Page through an owner's current (non-superseded, non-revoked) documents,
+    /// optionally filtered by type and linked state. `start` counts matches.
+    pub fn get_owner_documents(
+        env: Env,
+        owner: Address,
+        start: u32,
+        limit: u32,
+        doc_type: Option<DocumentType>,
+        linked: Option<bool>,
+    ) -> Vec<DocumentInfo> {
+        let limit = limit.min(MAX_PAGE_SIZE);
+        let mut page = Vec::new(&env);
+        let mut skipped: u32 = 0;
+
+        for doc_id in storage::get_owner_documents(&env, &owner).iter() {
+            if page.len() >= limit {
+                break;
+            }
+            let doc = storage::get_document(&env, doc_id).unwrap();
+            if doc.revoked || doc.superseded_by.is_some() {
+                continue;
+            }
+            if doc_type.as_ref().is_some_and(|t| *t != doc.doc_type) {
+                continue;
+            }
+            if linked.is_some_and(|l| l != doc.linked) {
+                continue;
+            }
+            if skipped < start {
+                skipped += 1;
+                continue;
+            }
+            page.push_back(doc);
+        }
+
+        page
+    }
+
     /// Get document info.
     pub fn get_document(env: Env, doc_id: DocId) -> Result<DocumentInfo, DocumentError> {
         storage::get_document(&env, doc_id).ok_or(DocumentError::DocumentNotFound)
     }
 }
+
+/// Shared body of the beneficiary access paths. A plain beneficiary is
+/// checked against the vault's list; with a `salt`, against its commitments.
+fn beneficiary_access(
+    env: &Env,
+    doc_id: DocId,
+    beneficiary: &Address,
+    salt: Option<&BytesN<32>>,
+) -> Result<DocumentAccess, DocumentError> {
+    let doc = storage::get_document(env, doc_id)
+        .ok_or(DocumentError::DocumentNotFound)?;
+
+    if doc.revoked {
+        return Err(DocumentError::DocumentRevoked);
+    }
+
+    if !doc.linked {
+        return Err(DocumentError::NotLinked);
+    }
+
+    let vault_contract = storage::get_vault_contract(env)
+        .ok_or(DocumentError::VaultContractNotSet)?;
+    let vault = VaultClient::new(env, &vault_contract);
+
+    let status = match vault.try_get_status(&doc.vault_id) {
+        Ok(Ok(status)) => status,
+        _ => return Err(DocumentError::AccessDenied),
+    };
+    if status != VaultStatus::Triggered && status != VaultStatus::Distributed {
+        return Err(DocumentError::VaultNotTriggered);
+    }
+
+    let is_beneficiary = match salt {
+        None => vault.try_is_beneficiary(&doc.vault_id, beneficiary),
+        Some(salt) => vault.try_is_committed_beneficiary(&doc.vault_id, beneficiary, salt),
+    };
+    match is_beneficiary {
+        Ok(Ok(true)) => {}
+        Ok(Ok(false)) => return Err(DocumentError::NotABeneficiary),
+        _ => return Err(DocumentError::AccessDenied),
+    }
+
+    if doc.release_after_trigger_secs.is_some() || doc.release_at.is_some() {
+        match release_of(env, &doc).unlocks_at {
+            Some(unlocks_at) if unlocks_at <= env.ledger().timestamp() => {}
+            _ => return Err(DocumentError::NotYetReleased),
+        }
+    }
+
+    record_access(env, doc_id, beneficiary);
+
+    let encrypted_key = storage::get_encrypted_key(env, doc_id, beneficiary)
+        .unwrap_or(Bytes::new(env));
+
+    Ok(DocumentAccess {
+        ipfs_cid: doc.ipfs_cid,
+        encrypted_key,
+        doc_type: doc.doc_type,
+        is_encrypted: doc.is_encrypted,
+    })
+}
+
+/// Only link documents to an existing, unclosed vault owned by the document's
+/// owner, so nobody can pad another owner's document list.
+fn ensure_vault_owned_by(env: &Env, vault_id: u64, owner: &Address) -> Result<(), DocumentError> {
+    let vault_contract =
+        storage::get_vault_contract(env).ok_or(DocumentError::VaultContractNotSet)?;
+
+    match VaultClient::new(env, &vault_contract).try_get_vault(&vault_id) {
+        Ok(Ok(vault)) if vault.status == VaultStatus::Closed => Err(DocumentError::VaultNotFound),
+        Ok(Ok(vault)) if vault.owner != *owner => Err(DocumentError::NotAuthorized),
+        Ok(Ok(_)) => Ok(()),
+        _ => Err(DocumentError::VaultNotFound),
+    }
+}
+
+/// Reject changes that would remove a document from a Triggered or Distributed
+/// vault. Only called for linked documents, so it fails closed: without a
+/// configured vault contract, or if the status lookup fails, nothing changes.
+fn ensure_vault_not_triggered(env: &Env, vault_id: u64) -> Result<(), DocumentError> {
+    let vault_contract =
+        storage::get_vault_contract(env).ok_or(DocumentError::VaultContractNotSet)?;
+
+    match VaultClient::new(env, &vault_contract).try_get_status(&vault_id) {
+        Ok(Ok(VaultStatus::Triggered)) | Ok(Ok(VaultStatus::Distributed)) => {
+            Err(DocumentError::VaultTriggered)
+        }
+        Ok(Ok(_)) => Ok(()),
+        _ => Err(DocumentError::AccessDenied),
+    }
+}
+
+/// Resolve a document's release schedule against its vault's trigger time.
+fn release_of(env: &Env, doc: &DocumentInfo) -> DocumentRelease {
+    let mut unlocks_at = doc.release_at;
+
+    if let Some(delay) = doc.release_after_trigger_secs {
+        let triggered_at = if doc.linked {
+            storage::get_vault_contract(env).and_then(|vault_contract| {
+                VaultClient::new(env, &vault_contract)
+                    .try_get_triggered_at(&doc.vault_id)
+                    .ok()
+                    .and_then(|r| r.ok())
+                    .flatten()
+            })
+        } else {
+            None
+        };
+        unlocks_at = triggered_at.map(|t| unlocks_at.map_or(t + delay, |at| at.max(t + delay)));
+    }
+
+    DocumentRelease {
+        doc_id: doc.id,
+        release_after_trigger_secs: doc.release_after_trigger_secs,
+        release_at: doc.release_at,
+        unlocks_at,
+    }
+}
+
+/// Domain prefixes keeping leaf and internal node hashes apart.
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+/// Levels in a batch tree of `count` leaves: `ceil(log2(count))`.
+fn tree_depth(count: u32) -> u32 {
+    32 - (count - 1).leading_zeros()
+}
+
+fn hash_leaf(env: &Env, leaf: &BytesN<32>) -> BytesN<32> {
+    let mut preimage = Bytes::from_array(env, &[LEAF_PREFIX]);
+    preimage.extend_from_array(&leaf.to_array());
+    env.crypto().sha256(&preimage).into()
+}
+
+fn hash_pair(env: &Env, a: &BytesN<32>, b: &BytesN<32>) -> BytesN<32> {
+    let (left, right) = if a.to_array() <= b.to_array() { (a, b) } else { (b, a) };
+    let mut preimage = Bytes::from_array(env, &[NODE_PREFIX]);
+    preimage.extend_from_array(&left.to_array());
+    preimage.extend_from_array(&right.to_array());
+    env.crypto().sha256(&preimage).into()
+}
+
+/// Mark access as granted and log it. Every access emits an event; the log
+/// keeps the most recent accesses, dropping the oldest once it is full.
+#[allow(deprecated)]
+fn record_access(env: &Env, doc_id: DocId, accessor: &Address) {
+    storage::grant_access(env, doc_id, accessor);
+
+    let mut log = storage::get_access_log(env, doc_id);
+    if log.len() >= MAX_ACCESS_RECORDS {
+        log.pop_front();
+    }
+    log.push_back(AccessRecord {
+        doc_id,
+        accessor: accessor.clone(),
+        timestamp: env.ledger().timestamp(),
+    });
+    storage::set_access_log(env, doc_id, &log);
+
+    env.events()
+        .publish((symbol_short!("doc"), symbol_short!("access")), (doc_id, accessor.clone()));
+}
+
+/// Reject state-changing calls while the admin has paused the contract.
+fn ensure_not_paused(env: &Env) -> Result<(), DocumentError> {
+    if storage::is_paused(env) {
+        return Err(DocumentError::Paused);
+    }
+    Ok(())
+}
@@ -10,4 +10,18 @@ pub enum DocumentError {
     AlreadyInitialized = 5,
     NotInitialized = 6,
     KeyNotFound = 7,
+    VaultContractNotSet = 8,
+    NotLinked = 9,
+    VaultNotTriggered = 10,
+    NotABeneficiary = 11,
+    AlreadySuperseded = 12,
+    DocumentRevoked = 13,
+    VaultTriggered = 14,
+    NotYetReleased = 15,
+    NotANotary = 16,
+    AlreadyEndorsed = 17,
+    BatchNotFound = 18,
+    EmptyBatch = 19,
+    Paused = 20,
+    VaultNotFound = 21,
 }
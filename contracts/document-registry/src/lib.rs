@@ -4,6 +4,7 @@ pub mod contract;
 pub mod errors;
 pub mod storage;
 pub mod types;
+pub mod vault;
 
 #[cfg(test)]
 mod test;
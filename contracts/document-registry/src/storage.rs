@@ -1,6 +1,6 @@
 use soroban_sdk::{contracttype, Address, Bytes, Env, Vec};
 
-use crate::types::{DocId, DocumentInfo};
+use crate::types::{AccessRecord, BatchId, BatchInfo, DocId, DocumentInfo, Endorsement};
 
 #[contracttype]
 #[derive(Clone)]
@@ -12,6 +12,15 @@ pub enum DataKey {
     EncryptedKey(DocId, Address),
     AccessGranted(DocId, Address),
     Initialized,
+    Paused,
+    VaultContract,
+    KeyHolders(DocId),
+    OwnerDocuments(Address),
+    Notary(Address),
+    Endorsements(DocId),
+    BatchCount,
+    Batch(BatchId),
+    AccessLog(DocId),
 }
 
 pub fn is_initialized(env: &Env) -> bool {
@@ -22,6 +31,14 @@ pub fn set_initialized(env: &Env) {
     env.storage().instance().set(&DataKey::Initialized, &true);
 }
 
+pub fn is_paused(env: &Env) -> bool {
+    env.storage().instance().get(&DataKey::Paused).unwrap_or(false)
+}
+
+pub fn set_paused(env: &Env, paused: bool) {
+    env.storage().instance().set(&DataKey::Paused, &paused);
+}
+
 pub fn get_admin(env: &Env) -> Address {
     env.storage().instance().get(&DataKey::Admin).unwrap()
 }
@@ -30,6 +47,14 @@ pub fn set_admin(env: &Env, admin: &Address) {
     env.storage().instance().set(&DataKey::Admin, admin);
 }
 
+pub fn get_vault_contract(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::VaultContract)
+}
+
+pub fn set_vault_contract(env: &Env, vault_contract: &Address) {
+    env.storage().instance().set(&DataKey::VaultContract, vault_contract);
+}
+
 pub fn get_doc_count(env: &Env) -> DocId {
     env.storage().instance().get(&DataKey::DocCount).unwrap_or(0)
 }
@@ -55,12 +80,50 @@ pub fn get_vault_documents(env: &Env, vault_id: u64) -> Vec<DocId> {
 
 pub fn add_vault_document(env: &Env, vault_id: u64, doc_id: DocId) {
     let mut docs = get_vault_documents(env, vault_id);
+    if docs.contains(doc_id) {
+        return;
+    }
     docs.push_back(doc_id);
     env.storage()
         .persistent()
         .set(&DataKey::VaultDocuments(vault_id), &docs);
 }
 
+pub fn remove_vault_document(env: &Env, vault_id: u64, doc_id: DocId) {
+    let mut docs = get_vault_documents(env, vault_id);
+    if let Some(index) = docs.first_index_of(doc_id) {
+        docs.remove(index);
+        env.storage()
+            .persistent()
+            .set(&DataKey::VaultDocuments(vault_id), &docs);
+    }
+}
+
+pub fn get_owner_documents(env: &Env, owner: &Address) -> Vec<DocId> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::OwnerDocuments(owner.clone()))
+        .unwrap_or(Vec::new(env))
+}
+
+pub fn add_owner_document(env: &Env, owner: &Address, doc_id: DocId) {
+    let mut docs = get_owner_documents(env, owner);
+    docs.push_back(doc_id);
+    env.storage()
+        .persistent()
+        .set(&DataKey::OwnerDocuments(owner.clone()), &docs);
+}
+
+pub fn remove_owner_document(env: &Env, owner: &Address, doc_id: DocId) {
+    let mut docs = get_owner_documents(env, owner);
+    if let Some(index) = docs.first_index_of(doc_id) {
+        docs.remove(index);
+        env.storage()
+            .persistent()
+            .set(&DataKey::OwnerDocuments(owner.clone()), &docs);
+    }
+}
+
 pub fn get_encrypted_key(env: &Env, doc_id: DocId, beneficiary: &Address) -> Option<Bytes> {
     env.storage()
         .persistent()
@@ -71,6 +134,31 @@ pub fn set_encrypted_key(env: &Env, doc_id: DocId, beneficiary: &Address, key: &
     env.storage()
         .persistent()
         .set(&DataKey::EncryptedKey(doc_id, beneficiary.clone()), key);
+
+    let mut holders = get_key_holders(env, doc_id);
+    if !holders.contains(beneficiary) {
+        holders.push_back(beneficiary.clone());
+        env.storage()
+            .persistent()
+            .set(&DataKey::KeyHolders(doc_id), &holders);
+    }
+}
+
+pub fn get_key_holders(env: &Env, doc_id: DocId) -> Vec<Address> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::KeyHolders(doc_id))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Delete every stored encrypted key for a document.
+pub fn remove_encrypted_keys(env: &Env, doc_id: DocId) {
+    for holder in get_key_holders(env, doc_id).iter() {
+        env.storage()
+            .persistent()
+            .remove(&DataKey::EncryptedKey(doc_id, holder));
+    }
+    env.storage().persistent().remove(&DataKey::KeyHolders(doc_id));
 }
 
 pub fn is_access_granted(env: &Env, doc_id: DocId, beneficiary: &Address) -> bool {
@@ -84,3 +172,63 @@ pub fn grant_access(env: &Env, doc_id: DocId, beneficiary: &Address) {
         .persistent()
         .set(&DataKey::AccessGranted(doc_id, beneficiary.clone()), &true);
 }
+
+pub fn is_notary(env: &Env, notary: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .has(&DataKey::Notary(notary.clone()))
+}
+
+pub fn set_notary(env: &Env, notary: &Address) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Notary(notary.clone()), &true);
+}
+
+pub fn remove_notary(env: &Env, notary: &Address) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::Notary(notary.clone()));
+}
+
+pub fn get_endorsements(env: &Env, doc_id: DocId) -> Vec<Endorsement> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Endorsements(doc_id))
+        .unwrap_or(Vec::new(env))
+}
+
+pub fn set_endorsements(env: &Env, doc_id: DocId, endorsements: &Vec<Endorsement>) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Endorsements(doc_id), endorsements);
+}
+
+pub fn get_batch_count(env: &Env) -> BatchId {
+    env.storage().instance().get(&DataKey::BatchCount).unwrap_or(0)
+}
+
+pub fn set_batch_count(env: &Env, count: BatchId) {
+    env.storage().instance().set(&DataKey::BatchCount, &count);
+}
+
+pub fn get_batch(env: &Env, batch_id: BatchId) -> Option<BatchInfo> {
+    env.storage().persistent().get(&DataKey::Batch(batch_id))
+}
+
+pub fn set_batch(env: &Env, batch_id: BatchId, batch: &BatchInfo) {
+    env.storage().persistent().set(&DataKey::Batch(batch_id), batch);
+}
+
+pub fn get_access_log(env: &Env, doc_id: DocId) -> Vec<AccessRecord> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::AccessLog(doc_id))
+        .unwrap_or(Vec::new(env))
+}
+
+pub fn set_access_log(env: &Env, doc_id: DocId, log: &Vec<AccessRecord>) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::AccessLog(doc_id), log);
+}
@@ -1,9 +1,15 @@
 #![cfg(test)]
 
 use super::*;
-use soroban_sdk::{testutils::Address as _, Address, Bytes, BytesN, Env, String};
+use soroban_sdk::{
+    contract, contractimpl,
+    testutils::{Address as _, Ledger},
+    Address, Bytes, BytesN, Env, String,
+};
 use contract::{DocumentRegistryContract, DocumentRegistryContractClient};
+use errors::DocumentError;
 use types::DocumentType;
+use vault::{VaultInfo, VaultStatus};
 
 // ---------------------------------------------------------------------------
 // Helper: set up env + initialized client
@@ -23,6 +29,75 @@ fn setup() -> (Env, DocumentRegistryContractClient<'static>, Address, Address) {
     (env, client, admin, owner)
 }
 
+// ---------------------------------------------------------------------------
+// Helper: a stub vault contract holding just the vaults a test opens
+// ---------------------------------------------------------------------------
+#[contract]
+struct StubVault;
+
+#[contractimpl]
+impl StubVault {
+    pub fn open_vault(env: Env, vault_id: u64, owner: Address) {
+        let vault = VaultInfo {
+            id: vault_id,
+            owner,
+            token: env.current_contract_address(),
+            status: VaultStatus::Active,
+            created_at: 0,
+            last_updated: 0,
+        };
+        env.storage().persistent().set(&vault_id, &vault);
+    }
+
+    pub fn set_status(env: Env, vault_id: u64, status: VaultStatus) {
+        let mut vault = Self::get_vault(env.clone(), vault_id);
+        vault.status = status;
+        env.storage().persistent().set(&vault_id, &vault);
+    }
+
+    pub fn get_vault(env: Env, vault_id: u64) -> VaultInfo {
+        env.storage().persistent().get(&vault_id).expect("vault not found")
+    }
+
+    pub fn get_status(env: Env, vault_id: u64) -> VaultStatus {
+        Self::get_vault(env, vault_id).status
+    }
+
+    pub fn get_triggered_at(_env: Env, _vault_id: u64) -> Option<u64> {
+        None
+    }
+}
+
+fn setup_with_vault() -> (
+    Env,
+    DocumentRegistryContractClient<'static>,
+    StubVaultClient<'static>,
+    Address,
+) {
+    let (env, client, _admin, owner) = setup();
+    let vault_contract = env.register(StubVault, ());
+    client.set_vault_contract(&vault_contract);
+    let vault = StubVaultClient::new(&env, &vault_contract);
+    (env, client, vault, owner)
+}
+
+// ---------------------------------------------------------------------------
+// Helpers: Merkle leaf and parent hashes, matching the contract
+// ---------------------------------------------------------------------------
+fn hash_leaf(env: &Env, file: &BytesN<32>) -> BytesN<32> {
+    let mut preimage = Bytes::from_array(env, &[0u8]);
+    preimage.extend_from_array(&file.to_array());
+    env.crypto().sha256(&preimage).into()
+}
+
+fn hash_sorted_pair(env: &Env, a: &BytesN<32>, b: &BytesN<32>) -> BytesN<32> {
+    let (left, right) = if a.to_array() <= b.to_array() { (a, b) } else { (b, a) };
+    let mut preimage = Bytes::from_array(env, &[1u8]);
+    preimage.extend_from_array(&left.to_array());
+    preimage.extend_from_array(&right.to_array());
+    env.crypto().sha256(&preimage).into()
+}
+
 // ---------------------------------------------------------------------------
 // 1. test_initialize
 // ---------------------------------------------------------------------------
@@ -73,7 +148,7 @@ fn test_register_document() {
 
     let doc = client.get_document(&doc_id);
     assert_eq!(doc.owner, owner);
-    assert_eq!(doc.is_encrypted, false);
+    assert!(!doc.is_encrypted);
 }
 
 // ---------------------------------------------------------------------------
@@ -136,7 +211,7 @@ fn test_verify_document() {
     );
 
     let proof = client.verify_document(&doc_id);
-    assert_eq!(proof.exists, true);
+    assert!(proof.exists);
     assert_eq!(proof.doc_hash, hash);
 }
 
@@ -156,7 +231,7 @@ fn test_verify_nonexistent() {
 // ---------------------------------------------------------------------------
 #[test]
 fn test_link_to_vault() {
-    let (env, client, _admin, owner) = setup();
+    let (env, client, vault, owner) = setup_with_vault();
 
     let cid = String::from_str(&env, "QmLinkMe");
     let hash = BytesN::from_array(&env, &[5u8; 32]);
@@ -169,12 +244,24 @@ fn test_link_to_vault() {
         &true,
     );
 
+    // Unknown, foreign and closed vaults are refused
+    let result = client.try_link_to_vault(&doc_id, &41u64);
+    assert_eq!(result.err().unwrap().unwrap(), DocumentError::VaultNotFound);
+    vault.open_vault(&41u64, &Address::generate(&env));
+    let result = client.try_link_to_vault(&doc_id, &41u64);
+    assert_eq!(result.err().unwrap().unwrap(), DocumentError::NotAuthorized);
+    vault.open_vault(&43u64, &owner);
+    vault.set_status(&43u64, &VaultStatus::Closed);
+    let result = client.try_link_to_vault(&doc_id, &43u64);
+    assert_eq!(result.err().unwrap().unwrap(), DocumentError::VaultNotFound);
+
     let vault_id: u64 = 42;
+    vault.open_vault(&vault_id, &owner);
     client.link_to_vault(&doc_id, &vault_id);
 
     // Verify the document is now linked
     let doc = client.get_document(&doc_id);
-    assert_eq!(doc.linked, true);
+    assert!(doc.linked);
     assert_eq!(doc.vault_id, vault_id);
 
     // Verify vault documents list contains the doc
@@ -209,7 +296,7 @@ fn test_store_and_get_encrypted_key() {
     // Grant access to verify the encrypted key is returned correctly
     let access = client.grant_access(&doc_id, &beneficiary);
     assert_eq!(access.encrypted_key, encrypted_key);
-    assert_eq!(access.is_encrypted, true);
+    assert!(access.is_encrypted);
 }
 
 // ---------------------------------------------------------------------------
@@ -237,7 +324,7 @@ fn test_grant_access() {
     // Verify DocumentAccess fields
     assert_eq!(access.ipfs_cid, String::from_str(&env, "QmAccess"));
     assert_eq!(access.doc_type, DocumentType::Will);
-    assert_eq!(access.is_encrypted, false);
+    assert!(!access.is_encrypted);
     // No key stored, so encrypted_key should be empty
     assert_eq!(access.encrypted_key, Bytes::new(&env));
 }
@@ -247,9 +334,10 @@ fn test_grant_access() {
 // ---------------------------------------------------------------------------
 #[test]
 fn test_get_vault_documents() {
-    let (env, client, _admin, owner) = setup();
+    let (env, client, vault, owner) = setup_with_vault();
 
     let vault_id: u64 = 100;
+    vault.open_vault(&vault_id, &owner);
 
     // Register and link three documents to the same vault
     let cid1 = String::from_str(&env, "QmVault1");
@@ -291,3 +379,484 @@ fn test_get_vault_documents() {
     assert_eq!(vault_docs.get(1).unwrap(), id2);
     assert_eq!(vault_docs.get(2).unwrap(), id3);
 }
+
+// ---------------------------------------------------------------------------
+// 11. test_document_access_requires_link_and_vault_contract
+// ---------------------------------------------------------------------------
+#[test]
+fn test_document_access_requires_link_and_vault_contract() {
+    let (env, client, _admin, owner) = setup();
+
+    let beneficiary = Address::generate(&env);
+
+    let cid = String::from_str(&env, "QmGated");
+    let hash = BytesN::from_array(&env, &[8u8; 32]);
+    let doc_id = client.register_document(
+        &owner,
+        &cid,
+        &hash,
+        &DocumentType::Will,
+        &true,
+    );
+
+    // Unlinked document => NotLinked
+    let result = client.try_get_document_access(&doc_id, &beneficiary);
+    assert_eq!(result.err().unwrap().unwrap(), DocumentError::NotLinked);
+
+    // No vault contract configured => nothing can be linked
+    let result = client.try_link_to_vault(&doc_id, &1u64);
+    assert_eq!(result.err().unwrap().unwrap(), DocumentError::VaultContractNotSet);
+    assert!(!client.has_access(&doc_id, &beneficiary));
+}
+
+// ---------------------------------------------------------------------------
+// 12. test_supersede_document
+// ---------------------------------------------------------------------------
+#[test]
+fn test_supersede_document() {
+    let (env, client, vault, owner) = setup_with_vault();
+
+    let vault_id: u64 = 7;
+    vault.open_vault(&vault_id, &owner);
+    let hash_v1 = BytesN::from_array(&env, &[21u8; 32]);
+    let v1 = client.register_document(
+        &owner,
+        &String::from_str(&env, "QmWillV1"),
+        &hash_v1,
+        &DocumentType::Will,
+        &true,
+    );
+    client.link_to_vault(&v1, &vault_id);
+
+    let hash_v2 = BytesN::from_array(&env, &[22u8; 32]);
+    let v2 = client.supersede_document(&v1, &String::from_str(&env, "QmWillV2"), &hash_v2);
+
+    // The new version inherits type, encryption and vault link
+    let doc = client.get_document(&v2);
+    assert_eq!(doc.version, 2);
+    assert_eq!(doc.previous_id, Some(v1));
+    assert_eq!(doc.doc_type, DocumentType::Will);
+    assert!(doc.linked);
+    assert_eq!(client.get_document(&v1).superseded_by, Some(v2));
+
+    // Only the current version is listed by default
+    let current = client.get_vault_documents(&vault_id);
+    assert_eq!(current.len(), 1);
+    assert_eq!(current.get(0).unwrap(), v2);
+    assert_eq!(client.get_all_vault_documents(&vault_id).len(), 2);
+
+    // Proofs report whether the hash is the latest version
+    let old_proof = client.verify_document(&v1);
+    assert_eq!(old_proof.doc_hash, hash_v1);
+    assert!(!old_proof.is_latest);
+    assert!(client.verify_document(&v2).is_latest);
+
+    // A superseded version cannot be superseded again
+    let result = client.try_supersede_document(&v1, &String::from_str(&env, "QmFork"), &hash_v2);
+    assert_eq!(result.err().unwrap().unwrap(), DocumentError::AlreadySuperseded);
+}
+
+// ---------------------------------------------------------------------------
+// 13. test_document_history
+// ---------------------------------------------------------------------------
+#[test]
+fn test_document_history() {
+    let (env, client, _admin, owner) = setup();
+
+    let v1 = client.register_document(
+        &owner,
+        &String::from_str(&env, "QmLetterV1"),
+        &BytesN::from_array(&env, &[31u8; 32]),
+        &DocumentType::PersonalLetter,
+        &false,
+    );
+    let v2 = client.supersede_document(
+        &v1,
+        &String::from_str(&env, "QmLetterV2"),
+        &BytesN::from_array(&env, &[32u8; 32]),
+    );
+    let v3 = client.supersede_document(
+        &v2,
+        &String::from_str(&env, "QmLetterV3"),
+        &BytesN::from_array(&env, &[33u8; 32]),
+    );
+
+    // History is the same from any version, oldest first
+    let history = client.get_document_history(&v2);
+    assert_eq!(history.len(), 3);
+    assert_eq!(history.get(0).unwrap().id, v1);
+    assert_eq!(history.get(1).unwrap().id, v2);
+    assert_eq!(history.get(2).unwrap().id, v3);
+    assert_eq!(client.get_document_history(&v1).len(), 3);
+}
+
+// ---------------------------------------------------------------------------
+// 14. test_link_twice_fails
+// ---------------------------------------------------------------------------
+#[test]
+fn test_link_twice_fails() {
+    let (env, client, vault, owner) = setup_with_vault();
+
+    vault.open_vault(&5u64, &owner);
+    vault.open_vault(&6u64, &owner);
+
+    let doc_id = client.register_document(
+        &owner,
+        &String::from_str(&env, "QmOnce"),
+        &BytesN::from_array(&env, &[41u8; 32]),
+        &DocumentType::Deed,
+        &false,
+    );
+    client.link_to_vault(&doc_id, &5u64);
+
+    // Re-linking (same or other vault) => AlreadyLinked, list stays deduplicated
+    let result = client.try_link_to_vault(&doc_id, &5u64);
+    assert_eq!(result.err().unwrap().unwrap(), DocumentError::AlreadyLinked);
+    let result = client.try_link_to_vault(&doc_id, &6u64);
+    assert_eq!(result.err().unwrap().unwrap(), DocumentError::AlreadyLinked);
+    assert_eq!(client.get_vault_documents(&5u64).len(), 1);
+    assert_eq!(client.get_document(&doc_id).vault_id, 5);
+}
+
+// ---------------------------------------------------------------------------
+// 15. test_unlink_from_vault
+// ---------------------------------------------------------------------------
+#[test]
+fn test_unlink_from_vault() {
+    let (env, client, vault, owner) = setup_with_vault();
+
+    vault.open_vault(&5u64, &owner);
+    vault.open_vault(&6u64, &owner);
+
+    let doc_id = client.register_document(
+        &owner,
+        &String::from_str(&env, "QmUnlink"),
+        &BytesN::from_array(&env, &[42u8; 32]),
+        &DocumentType::Certificate,
+        &false,
+    );
+
+    let result = client.try_unlink_from_vault(&doc_id);
+    assert_eq!(result.err().unwrap().unwrap(), DocumentError::NotLinked);
+
+    // A vault contract that cannot confirm the status makes unlinking fail closed
+    client.link_to_vault(&doc_id, &5u64);
+    client.set_vault_contract(&Address::generate(&env));
+    let result = client.try_unlink_from_vault(&doc_id);
+    assert_eq!(result.err().unwrap().unwrap(), DocumentError::AccessDenied);
+
+    client.set_vault_contract(&vault.address);
+    client.unlink_from_vault(&doc_id);
+    assert!(!client.get_document(&doc_id).linked);
+    assert_eq!(client.get_vault_documents(&5u64).len(), 0);
+
+    // Can be linked again after unlinking
+    client.link_to_vault(&doc_id, &6u64);
+    assert_eq!(client.get_vault_documents(&6u64).len(), 1);
+}
+
+// ---------------------------------------------------------------------------
+// 16. test_revoke_document
+// ---------------------------------------------------------------------------
+#[test]
+fn test_revoke_document() {
+    let (env, client, vault, owner) = setup_with_vault();
+
+    vault.open_vault(&5u64, &owner);
+
+    let beneficiary = Address::generate(&env);
+    let doc_id = client.register_document(
+        &owner,
+        &String::from_str(&env, "QmRevoke"),
+        &BytesN::from_array(&env, &[43u8; 32]),
+        &DocumentType::PersonalLetter,
+        &true,
+    );
+    client.link_to_vault(&doc_id, &5u64);
+    client.store_encrypted_key(&doc_id, &beneficiary, &Bytes::from_slice(&env, &[1u8; 16]));
+
+    client.revoke_document(&doc_id);
+
+    // Tombstoned: still readable, but no longer "exists" and no keys remain
+    let doc = client.get_document(&doc_id);
+    assert!(doc.revoked);
+    assert!(!doc.linked);
+    assert!(!client.verify_document(&doc_id).exists);
+    assert_eq!(client.get_vault_documents(&5u64).len(), 0);
+    let key = env.as_contract(&client.address, || {
+        storage::get_encrypted_key(&env, doc_id, &beneficiary)
+    });
+    assert!(key.is_none());
+
+    let result = client.try_grant_access(&doc_id, &beneficiary);
+    assert_eq!(result.err().unwrap().unwrap(), DocumentError::DocumentRevoked);
+    let result = client.try_revoke_document(&doc_id);
+    assert_eq!(result.err().unwrap().unwrap(), DocumentError::DocumentRevoked);
+}
+
+// ---------------------------------------------------------------------------
+// 17. test_release_schedule
+// ---------------------------------------------------------------------------
+#[test]
+fn test_release_schedule() {
+    let (env, client, vault, owner) = setup_with_vault();
+
+    let vault_id: u64 = 9;
+    vault.open_vault(&vault_id, &owner);
+    let birthday = client.register_document(
+        &owner,
+        &String::from_str(&env, "QmBirthdayLetter"),
+        &BytesN::from_array(&env, &[51u8; 32]),
+        &DocumentType::PersonalLetter,
+        &true,
+    );
+    let anniversary = client.register_document(
+        &owner,
+        &String::from_str(&env, "QmAnniversaryLetter"),
+        &BytesN::from_array(&env, &[52u8; 32]),
+        &DocumentType::PersonalLetter,
+        &true,
+    );
+    let will = client.register_document(
+        &owner,
+        &String::from_str(&env, "QmWill"),
+        &BytesN::from_array(&env, &[53u8; 32]),
+        &DocumentType::Will,
+        &true,
+    );
+    client.link_to_vault(&birthday, &vault_id);
+    client.link_to_vault(&anniversary, &vault_id);
+    client.link_to_vault(&will, &vault_id);
+
+    client.set_release_schedule(&birthday, &None, &Some(1_000u64));
+    client.set_release_schedule(&anniversary, &Some(365 * 24 * 60 * 60u64), &None);
+
+    assert_eq!(client.get_unlock_time(&birthday), Some(1_000));
+    // Trigger-relative release is unknown until the vault triggers
+    assert_eq!(client.get_unlock_time(&anniversary), None);
+    assert_eq!(client.get_unlock_time(&will), None);
+
+    let upcoming = client.get_upcoming_releases(&vault_id);
+    assert_eq!(upcoming.len(), 2);
+    assert_eq!(upcoming.get(0).unwrap().doc_id, birthday);
+    assert_eq!(upcoming.get(1).unwrap().doc_id, anniversary);
+
+    // Once the absolute time passes it is no longer upcoming
+    env.ledger().set_timestamp(1_000);
+    let upcoming = client.get_upcoming_releases(&vault_id);
+    assert_eq!(upcoming.len(), 1);
+    assert_eq!(upcoming.get(0).unwrap().doc_id, anniversary);
+}
+
+// ---------------------------------------------------------------------------
+// 18. test_get_owner_documents
+// ---------------------------------------------------------------------------
+#[test]
+fn test_get_owner_documents() {
+    let (env, client, vault, owner) = setup_with_vault();
+
+    vault.open_vault(&1u64, &owner);
+
+    let other_owner = Address::generate(&env);
+    let mut ids = soroban_sdk::Vec::new(&env);
+    for i in 0..5u8 {
+        let doc_type = if i % 2 == 0 { DocumentType::Deed } else { DocumentType::Will };
+        let id = client.register_document(
+            &owner,
+            &String::from_str(&env, "QmLibrary"),
+            &BytesN::from_array(&env, &[60 + i; 32]),
+            &doc_type,
+            &false,
+        );
+        ids.push_back(id);
+    }
+    client.register_document(
+        &other_owner,
+        &String::from_str(&env, "QmSomeoneElse"),
+        &BytesN::from_array(&env, &[70u8; 32]),
+        &DocumentType::Deed,
+        &false,
+    );
+    client.link_to_vault(&ids.get(0).unwrap(), &1u64);
+    client.revoke_document(&ids.get(4).unwrap());
+
+    // Revoked documents are hidden; other owners' documents never show up
+    let all = client.get_owner_documents(&owner, &0, &10, &None, &None);
+    assert_eq!(all.len(), 4);
+
+    // Pagination over the matching set
+    let page = client.get_owner_documents(&owner, &1, &2, &None, &None);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get(0).unwrap().id, ids.get(1).unwrap());
+    assert_eq!(page.get(1).unwrap().id, ids.get(2).unwrap());
+
+    // Filters: type and linked state
+    let deeds = client.get_owner_documents(&owner, &0, &10, &Some(DocumentType::Deed), &None);
+    assert_eq!(deeds.len(), 2);
+    let unlinked = client.get_owner_documents(&owner, &0, &10, &None, &Some(false));
+    assert_eq!(unlinked.len(), 3);
+    let linked_deeds =
+        client.get_owner_documents(&owner, &0, &10, &Some(DocumentType::Deed), &Some(true));
+    assert_eq!(linked_deeds.len(), 1);
+    assert_eq!(linked_deeds.get(0).unwrap().id, ids.get(0).unwrap());
+
+    // Superseded versions are replaced by their successor
+    let v2 = client.supersede_document(
+        &ids.get(1).unwrap(),
+        &String::from_str(&env, "QmLibraryV2"),
+        &BytesN::from_array(&env, &[80u8; 32]),
+    );
+    let wills = client.get_owner_documents(&owner, &0, &10, &Some(DocumentType::Will), &None);
+    assert_eq!(wills.len(), 2);
+    assert_eq!(wills.get(1).unwrap().id, v2);
+}
+
+// ---------------------------------------------------------------------------
+// 19. test_notary_endorsement
+// ---------------------------------------------------------------------------
+#[test]
+fn test_notary_endorsement() {
+    let (env, client, _admin, owner) = setup();
+
+    let notary = Address::generate(&env);
+    let impostor = Address::generate(&env);
+    let statement = BytesN::from_array(&env, &[90u8; 32]);
+
+    let doc_id = client.register_document(
+        &owner,
+        &String::from_str(&env, "QmNotarizedWill"),
+        &BytesN::from_array(&env, &[91u8; 32]),
+        &DocumentType::Will,
+        &true,
+    );
+
+    // Unapproved address => NotANotary
+    let result = client.try_endorse(&impostor, &doc_id, &statement);
+    assert_eq!(result.err().unwrap().unwrap(), DocumentError::NotANotary);
+
+    client.add_notary(&notary);
+    assert!(client.is_notary(&notary));
+
+    env.ledger().set_timestamp(500);
+    client.endorse(&notary, &doc_id, &statement);
+
+    let result = client.try_endorse(&notary, &doc_id, &statement);
+    assert_eq!(result.err().unwrap().unwrap(), DocumentError::AlreadyEndorsed);
+
+    let proof = client.verify_document(&doc_id);
+    assert_eq!(proof.endorsements.len(), 1);
+    let endorsement = proof.endorsements.get(0).unwrap();
+    assert_eq!(endorsement.notary, notary);
+    assert_eq!(endorsement.statement_hash, statement);
+    assert_eq!(endorsement.timestamp, 500);
+
+    // Removing the notary blocks new endorsements but keeps the trail
+    client.remove_notary(&notary);
+    let other_statement = BytesN::from_array(&env, &[92u8; 32]);
+    assert!(client.try_endorse(&notary, &doc_id, &other_statement).is_err());
+    assert_eq!(client.verify_document(&doc_id).endorsements.len(), 1);
+}
+
+// ---------------------------------------------------------------------------
+// 20. test_register_batch_and_verify_inclusion
+// ---------------------------------------------------------------------------
+#[test]
+fn test_register_batch_and_verify_inclusion() {
+    let (env, client, _admin, owner) = setup();
+
+    // Four scans: root = H(H(a, b), H(c, d))
+    let a = BytesN::from_array(&env, &[1u8; 32]);
+    let b = BytesN::from_array(&env, &[2u8; 32]);
+    let c = BytesN::from_array(&env, &[3u8; 32]);
+    let d = BytesN::from_array(&env, &[4u8; 32]);
+    let ab = hash_sorted_pair(&env, &hash_leaf(&env, &a), &hash_leaf(&env, &b));
+    let cd = hash_sorted_pair(&env, &hash_leaf(&env, &c), &hash_leaf(&env, &d));
+    let root = hash_sorted_pair(&env, &ab, &cd);
+
+    let batch_id = client.register_batch(&owner, &root, &4, &DocumentType::Certificate);
+    assert_eq!(batch_id, 0);
+    let batch = client.get_batch(&batch_id);
+    assert_eq!(batch.count, 4);
+    assert_eq!(batch.merkle_root, root);
+
+    // Valid path for c: [leaf(d), ab]
+    let mut proof = soroban_sdk::Vec::new(&env);
+    proof.push_back(hash_leaf(&env, &d));
+    proof.push_back(ab.clone());
+    assert!(client.verify_inclusion(&batch_id, &c, &proof));
+
+    // Unknown leaf or wrong path => false
+    let stranger = BytesN::from_array(&env, &[9u8; 32]);
+    assert!(!client.verify_inclusion(&batch_id, &stranger, &proof));
+    let mut bad_proof = soroban_sdk::Vec::new(&env);
+    bad_proof.push_back(a.clone());
+    assert!(!client.verify_inclusion(&batch_id, &c, &bad_proof));
+
+    // An internal node is not a file, even with a valid-looking short proof
+    let mut node_proof = soroban_sdk::Vec::new(&env);
+    node_proof.push_back(cd.clone());
+    assert!(!client.verify_inclusion(&batch_id, &ab, &node_proof));
+
+    // Odd levels pair the last node with itself: three files, depth 2
+    let cc = hash_sorted_pair(&env, &hash_leaf(&env, &c), &hash_leaf(&env, &c));
+    let odd_root = hash_sorted_pair(&env, &ab, &cc);
+    let odd_batch = client.register_batch(&owner, &odd_root, &3, &DocumentType::Certificate);
+    let mut odd_proof = soroban_sdk::Vec::new(&env);
+    odd_proof.push_back(hash_leaf(&env, &c));
+    odd_proof.push_back(ab.clone());
+    assert!(client.verify_inclusion(&odd_batch, &c, &odd_proof));
+
+    // Empty batch and unknown batch are rejected
+    let result = client.try_register_batch(&owner, &root, &0, &DocumentType::Other);
+    assert_eq!(result.err().unwrap().unwrap(), DocumentError::EmptyBatch);
+    let result = client.try_verify_inclusion(&99, &c, &proof);
+    assert_eq!(result.err().unwrap().unwrap(), DocumentError::BatchNotFound);
+}
+
+// ---------------------------------------------------------------------------
+// 21. test_access_log
+// ---------------------------------------------------------------------------
+#[test]
+fn test_access_log() {
+    let (env, client, _admin, owner) = setup();
+
+    let heir1 = Address::generate(&env);
+    let heir2 = Address::generate(&env);
+    let doc_id = client.register_document(
+        &owner,
+        &String::from_str(&env, "QmLoggedWill"),
+        &BytesN::from_array(&env, &[95u8; 32]),
+        &DocumentType::Will,
+        &true,
+    );
+    assert_eq!(client.get_access_log(&doc_id).len(), 0);
+
+    env.ledger().set_timestamp(100);
+    client.grant_access(&doc_id, &heir1);
+    env.ledger().set_timestamp(200);
+    client.grant_access(&doc_id, &heir2);
+
+    // Repeated access by the same accessor is logged again
+    env.ledger().set_timestamp(300);
+    client.grant_access(&doc_id, &heir1);
+
+    let log = client.get_access_log(&doc_id);
+    assert_eq!(log.len(), 3);
+    let first = log.get(0).unwrap();
+    assert_eq!(first.doc_id, doc_id);
+    assert_eq!(first.accessor, heir1);
+    assert_eq!(first.timestamp, 100);
+    assert_eq!(log.get(1).unwrap().accessor, heir2);
+    assert_eq!(log.get(2).unwrap().accessor, heir1);
+    assert_eq!(log.get(2).unwrap().timestamp, 300);
+
+    // Once full, the oldest entries make way for the newest
+    for i in 0..100u64 {
+        env.ledger().set_timestamp(1_000 + i);
+        client.grant_access(&doc_id, &heir2);
+    }
+    let log = client.get_access_log(&doc_id);
+    assert_eq!(log.len(), 100);
+    assert_eq!(log.get(0).unwrap().timestamp, 1_000);
+    assert_eq!(log.get(99).unwrap().timestamp, 1_099);
+}
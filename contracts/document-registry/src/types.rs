@@ -1,6 +1,7 @@
-use soroban_sdk::{contracttype, Address, Bytes, BytesN, String};
+use soroban_sdk::{contracttype, Address, Bytes, BytesN, String, Vec};
 
 pub type DocId = u64;
+pub type BatchId = u64;
 
 #[contracttype]
 #[derive(Clone, Debug, PartialEq)]
@@ -26,6 +27,12 @@ pub struct DocumentInfo {
     pub registered_at: u64,
     pub vault_id: u64,
     pub linked: bool,
+    pub version: u32,
+    pub previous_id: Option<DocId>,
+    pub superseded_by: Option<DocId>,
+    pub revoked: bool,
+    pub release_after_trigger_secs: Option<u64>,
+    pub release_at: Option<u64>,
 }
 
 #[contracttype]
@@ -35,6 +42,17 @@ pub struct DocumentProof {
     pub doc_hash: BytesN<32>,
     pub registered_at: u64,
     pub ipfs_cid: String,
+    pub version: u32,
+    pub is_latest: bool,
+    pub endorsements: Vec<Endorsement>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct Endorsement {
+    pub notary: Address,
+    pub statement_hash: BytesN<32>,
+    pub timestamp: u64,
 }
 
 #[contracttype]
@@ -45,3 +63,34 @@ pub struct DocumentAccess {
     pub doc_type: DocumentType,
     pub is_encrypted: bool,
 }
+
+/// A scheduled release. `unlocks_at` is `None` while it still depends on an
+/// unknown trigger time.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct DocumentRelease {
+    pub doc_id: DocId,
+    pub release_after_trigger_secs: Option<u64>,
+    pub release_at: Option<u64>,
+    pub unlocks_at: Option<u64>,
+}
+
+/// A bundle of files anchored by a single Merkle root.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct BatchInfo {
+    pub id: BatchId,
+    pub owner: Address,
+    pub merkle_root: BytesN<32>,
+    pub count: u32,
+    pub doc_type: DocumentType,
+    pub registered_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct AccessRecord {
+    pub doc_id: DocId,
+    pub accessor: Address,
+    pub timestamp: u64,
+}
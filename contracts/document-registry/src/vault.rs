@@ -0,0 +1,35 @@
+use soroban_sdk::{contractclient, contracttype, Address, BytesN, Env};
+
+/// Mirror of the vault contract's `VaultStatus`, used to decode cross-contract results.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum VaultStatus {
+    Active,
+    Alert,
+    GracePeriod,
+    Triggered,
+    Distributed,
+    Closed,
+}
+
+/// Mirror of the vault contract's `VaultInfo`.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct VaultInfo {
+    pub id: u64,
+    pub owner: Address,
+    pub token: Address,
+    pub status: VaultStatus,
+    pub created_at: u64,
+    pub last_updated: u64,
+}
+
+/// The subset of the vault contract interface the registry depends on.
+#[contractclient(name = "VaultClient")]
+pub trait VaultInterface {
+    fn get_vault(env: Env, vault_id: u64) -> VaultInfo;
+    fn get_status(env: Env, vault_id: u64) -> VaultStatus;
+    fn is_beneficiary(env: Env, vault_id: u64, address: Address) -> bool;
+    fn is_committed_beneficiary(env: Env, vault_id: u64, address: Address, salt: BytesN<32>) -> bool;
+    fn get_triggered_at(env: Env, vault_id: u64) -> Option<u64>;
+}
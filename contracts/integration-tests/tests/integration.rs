@@ -1,7 +1,7 @@
 #![cfg(test)]
 
 use soroban_sdk::{
-    testutils::Address as _, Address, Bytes, BytesN, Env, String, Vec,
+    testutils::{Address as _, Ledger}, Address, Bytes, BytesN, Env, String, Vec,
 };
 use soroban_sdk::token::{StellarAssetClient, TokenClient};
 
@@ -11,8 +11,9 @@ use soroban_sdk::token::{StellarAssetClient, TokenClient};
 
 use pulse_vault::contract::{VaultContract, VaultContractClient};
 use pulse_vault::types::{
-    Beneficiary as VaultBeneficiary, VaultId, VaultStatus,
+    Beneficiary as VaultBeneficiary, BeneficiaryCommitment, VaultId, VaultStatus,
 };
+use pulse_vault::errors::VaultError;
 
 use pulse_proof_of_life::contract::{ProofOfLifeContract, ProofOfLifeContractClient};
 use pulse_proof_of_life::types::VerificationSource;
@@ -21,6 +22,7 @@ use pulse_beneficiary::contract::{BeneficiaryContract, BeneficiaryContractClient
 use pulse_beneficiary::types::Beneficiary;
 
 use pulse_document_registry::contract::{DocumentRegistryContract, DocumentRegistryContractClient};
+use pulse_document_registry::errors::DocumentError;
 use pulse_document_registry::types::DocumentType;
 
 // ===========================================================================
@@ -99,6 +101,8 @@ fn setup_full_env<'a>() -> TestEnv<'a> {
     let doc_addr = env.register(DocumentRegistryContract, ());
     let doc_client = DocumentRegistryContractClient::new(&env, &doc_addr);
     doc_client.initialize(&admin);
+    doc_client.set_vault_contract(&vault_addr);
+    vault_client.set_document_registry(&doc_addr);
 
     TestEnv {
         env,
@@ -141,8 +145,8 @@ fn test_full_inheritance_lifecycle() {
     assert_eq!(vault_id, 0);
 
     let deposit_amount: i128 = 500_000;
-    t.vault_client.deposit(&vault_id, &owner, &deposit_amount);
-    assert_eq!(t.vault_client.get_balance(&vault_id), deposit_amount);
+    t.vault_client.deposit(&vault_id, &token_addr, &owner, &deposit_amount);
+    assert_eq!(t.vault_client.get_balance(&vault_id, &token_addr), deposit_amount);
 
     // ── 3. Register PoL model for owner ──────────────────────────────────
     let weights = create_weights(&t.env);
@@ -278,7 +282,7 @@ fn test_full_inheritance_lifecycle() {
     // ── 12. Withdrawals should fail (vault is Triggered, not Active) ────
     let withdraw_result =
         t.vault_client
-            .try_withdraw(&vault_id, &owner, &1_000);
+            .try_withdraw(&vault_id, &token_addr, &owner, &1_000);
     assert!(withdraw_result.is_err());
 
     // ── 13. Record claims on beneficiary contract ───────────────────────
@@ -321,7 +325,7 @@ fn test_full_inheritance_lifecycle() {
 
     // ── Final assertions ────────────────────────────────────────────────
     // Vault balance is still tracked (actual distribution is external)
-    assert_eq!(t.vault_client.get_balance(&vault_id), deposit_amount);
+    assert_eq!(t.vault_client.get_balance(&vault_id, &token_addr), deposit_amount);
     // Document is verified
     let proof = t.doc_client.verify_document(&doc_id);
     assert!(proof.exists);
@@ -345,7 +349,7 @@ fn test_emergency_recovery() {
     token_admin_client.mint(&owner, &100_000);
 
     let vault_id = t.vault_client.create_vault(&owner, &token_addr);
-    t.vault_client.deposit(&vault_id, &owner, &50_000);
+    t.vault_client.deposit(&vault_id, &token_addr, &owner, &50_000);
 
     // Register PoL model and link vault
     let weights = create_weights(&t.env);
@@ -383,7 +387,7 @@ fn test_emergency_recovery() {
 
     // Withdrawal should fail while in Alert
     let withdraw_attempt =
-        t.vault_client.try_withdraw(&vault_id, &owner, &1_000);
+        t.vault_client.try_withdraw(&vault_id, &token_addr, &owner, &1_000);
     assert!(withdraw_attempt.is_err());
 
     // ── 3. Emergency check-in => score back to 10000 ────────────────────
@@ -399,13 +403,13 @@ fn test_emergency_recovery() {
     );
 
     // ── 5. Verify vault is Active, deposits can be withdrawn ────────────
-    t.vault_client.withdraw(&vault_id, &owner, &20_000);
-    assert_eq!(t.vault_client.get_balance(&vault_id), 30_000);
+    t.vault_client.withdraw(&vault_id, &token_addr, &owner, &20_000);
+    assert_eq!(t.vault_client.get_balance(&vault_id, &token_addr), 30_000);
     assert_eq!(token_client.balance(&owner), 70_000); // 100k - 50k deposited + 20k withdrawn
 
     // Additional deposit should also work
-    t.vault_client.deposit(&vault_id, &owner, &10_000);
-    assert_eq!(t.vault_client.get_balance(&vault_id), 40_000);
+    t.vault_client.deposit(&vault_id, &token_addr, &owner, &10_000);
+    assert_eq!(t.vault_client.get_balance(&vault_id, &token_addr), 40_000);
 }
 
 // ===========================================================================
@@ -426,7 +430,7 @@ fn test_document_inheritance_flow() {
     token_admin_client.mint(&owner, &100_000);
 
     let vault_id = t.vault_client.create_vault(&owner, &token_addr);
-    t.vault_client.deposit(&vault_id, &owner, &50_000);
+    t.vault_client.deposit(&vault_id, &token_addr, &owner, &50_000);
 
     // ── 2. Register public document ─────────────────────────────────────
     let public_cid =
@@ -542,3 +546,350 @@ fn test_document_inheritance_flow() {
         BytesN::from_array(&t.env, &[2u8; 32])
     );
 }
+
+// ===========================================================================
+// Test 4 - Beneficiary-Gated Document Access
+// ===========================================================================
+
+#[test]
+fn test_beneficiary_document_access_gated_by_trigger() {
+    let t = setup_full_env();
+
+    let owner = Address::generate(&t.env);
+    let heir = Address::generate(&t.env);
+    let outsider = Address::generate(&t.env);
+    let token_addr = Address::generate(&t.env);
+
+    // ── 1. Vault with a single heir ─────────────────────────────────────
+    let vault_id = t.vault_client.create_vault(&owner, &token_addr);
+    let mut benefs = Vec::new(&t.env);
+    benefs.push_back(VaultBeneficiary {
+        address: heir.clone(),
+        percentage: 10_000,
+        claimed: false,
+    });
+    t.vault_client.set_beneficiaries(&vault_id, &benefs);
+
+    // ── 2. Encrypted will linked to the vault with a wrapped key ────────
+    let doc_id = t.doc_client.register_document(
+        &owner,
+        &String::from_str(&t.env, "QmGatedWillCID"),
+        &BytesN::from_array(&t.env, &[4u8; 32]),
+        &DocumentType::Will,
+        &true,
+    );
+    t.doc_client.link_to_vault(&doc_id, &vault_id);
+    let wrapped_key = Bytes::from_slice(&t.env, &[99u8; 32]);
+    t.doc_client.store_encrypted_key(&doc_id, &heir, &wrapped_key);
+
+    // ── 3. Active vault => VaultNotTriggered ────────────────────────────
+    let early = t.doc_client.try_get_document_access(&doc_id, &heir);
+    assert_eq!(early.err().unwrap().unwrap(), DocumentError::VaultNotTriggered);
+
+    // ── 4. Trigger the vault ────────────────────────────────────────────
+    t.vault_client
+        .transition_status(&vault_id, &t.admin, &VaultStatus::Triggered);
+
+    // Outsider => NotABeneficiary
+    let denied = t.doc_client.try_get_document_access(&doc_id, &outsider);
+    assert_eq!(denied.err().unwrap().unwrap(), DocumentError::NotABeneficiary);
+
+    // ── 5. Heir retrieves the key without any admin call ────────────────
+    let access = t.doc_client.get_document_access(&doc_id, &heir);
+    assert_eq!(access.encrypted_key, wrapped_key);
+    assert!(access.is_encrypted);
+    assert!(t.doc_client.has_access(&doc_id, &heir));
+    assert!(!t.doc_client.has_access(&doc_id, &outsider));
+
+    // ── 6. The retrieval is on the access log ───────────────────────────
+    let log = t.doc_client.get_access_log(&doc_id);
+    assert_eq!(log.len(), 1);
+    assert_eq!(log.get(0).unwrap().accessor, heir);
+}
+
+// ===========================================================================
+// Test 5 - Documents Cannot Disappear After Trigger
+// ===========================================================================
+
+#[test]
+fn test_document_removal_blocked_after_trigger() {
+    let t = setup_full_env();
+
+    let owner = Address::generate(&t.env);
+    let token_addr = Address::generate(&t.env);
+    let vault_id = t.vault_client.create_vault(&owner, &token_addr);
+
+    let doc_id = t.doc_client.register_document(
+        &owner,
+        &String::from_str(&t.env, "QmProtectedDeed"),
+        &BytesN::from_array(&t.env, &[5u8; 32]),
+        &DocumentType::Deed,
+        &false,
+    );
+    t.doc_client.link_to_vault(&doc_id, &vault_id);
+
+    t.vault_client
+        .transition_status(&vault_id, &t.admin, &VaultStatus::Triggered);
+
+    let unlink = t.doc_client.try_unlink_from_vault(&doc_id);
+    assert_eq!(unlink.err().unwrap().unwrap(), DocumentError::VaultTriggered);
+    let revoke = t.doc_client.try_revoke_document(&doc_id);
+    assert_eq!(revoke.err().unwrap().unwrap(), DocumentError::VaultTriggered);
+
+    assert_eq!(t.doc_client.get_vault_documents(&vault_id).len(), 1);
+    assert!(t.doc_client.verify_document(&doc_id).exists);
+}
+
+// ===========================================================================
+// Test 6 - Time-Released Posthumous Letter
+// ===========================================================================
+
+#[test]
+fn test_time_released_letter() {
+    let t = setup_full_env();
+
+    let owner = Address::generate(&t.env);
+    let heir = Address::generate(&t.env);
+    let token_addr = Address::generate(&t.env);
+
+    let vault_id = t.vault_client.create_vault(&owner, &token_addr);
+    let mut benefs = Vec::new(&t.env);
+    benefs.push_back(VaultBeneficiary {
+        address: heir.clone(),
+        percentage: 10_000,
+        claimed: false,
+    });
+    t.vault_client.set_beneficiaries(&vault_id, &benefs);
+
+    // ── 1. Letter released one year after trigger ───────────────────────
+    let one_year: u64 = 365 * 24 * 60 * 60;
+    let doc_id = t.doc_client.register_document(
+        &owner,
+        &String::from_str(&t.env, "QmEighteenthBirthdayLetter"),
+        &BytesN::from_array(&t.env, &[6u8; 32]),
+        &DocumentType::PersonalLetter,
+        &true,
+    );
+    t.doc_client.link_to_vault(&doc_id, &vault_id);
+    t.doc_client.set_release_schedule(&doc_id, &Some(one_year), &None);
+
+    // ── 2. Trigger at t = 1000 ──────────────────────────────────────────
+    t.env.ledger().set_timestamp(1_000);
+    t.vault_client
+        .transition_status(&vault_id, &t.admin, &VaultStatus::Triggered);
+    assert_eq!(t.vault_client.get_triggered_at(&vault_id), Some(1_000));
+
+    // Schedule is frozen once the vault has triggered
+    let reschedule = t.doc_client.try_set_release_schedule(&doc_id, &None, &None);
+    assert_eq!(reschedule.err().unwrap().unwrap(), DocumentError::VaultTriggered);
+
+    // ── 3. Too early => NotYetReleased, unlock time is queryable ────────
+    let early = t.doc_client.try_get_document_access(&doc_id, &heir);
+    assert_eq!(early.err().unwrap().unwrap(), DocumentError::NotYetReleased);
+    assert_eq!(t.doc_client.get_unlock_time(&doc_id), Some(1_000 + one_year));
+    assert_eq!(t.doc_client.get_upcoming_releases(&vault_id).len(), 1);
+
+    // ── 4. After the release time the heir can open it ──────────────────
+    t.env.ledger().set_timestamp(1_000 + one_year);
+    let access = t.doc_client.get_document_access(&doc_id, &heir);
+    assert_eq!(access.doc_type, DocumentType::PersonalLetter);
+    assert_eq!(t.doc_client.get_upcoming_releases(&vault_id).len(), 0);
+}
+
+// ===========================================================================
+// Test 7 - Ownership Transfer to a New Wallet
+// ===========================================================================
+
+#[test]
+fn test_ownership_transfer_migrates_pol_and_documents() {
+    let t = setup_full_env();
+
+    let owner = Address::generate(&t.env);
+    let new_wallet = Address::generate(&t.env);
+    let token_admin = Address::generate(&t.env);
+    let (token_addr, _token_client, token_admin_client) =
+        create_token(&t.env, &token_admin);
+    token_admin_client.mint(&owner, &10_000);
+
+    let vault_id = t.vault_client.create_vault(&owner, &token_addr);
+    t.vault_client.deposit(&vault_id, &token_addr, &owner, &10_000);
+
+    // Without a linked PoL there is no liveness score to vouch for the owner
+    let unlinked = t.vault_client.try_transfer_ownership(&vault_id, &new_wallet);
+    assert_eq!(unlinked.err().unwrap().unwrap(), VaultError::ProofOfLifeNotLinked);
+
+    t.pol_client
+        .register_model(&owner, &create_weights(&t.env), &100_000i128);
+    t.vault_client.link_proof_of_life(&vault_id, &t.pol_addr);
+    t.pol_client.link_vault(&owner, &t.vault_addr, &vault_id);
+
+    let doc_id = t.doc_client.register_document(
+        &owner,
+        &String::from_str(&t.env, "QmDeed"),
+        &BytesN::from_array(&t.env, &[7u8; 32]),
+        &DocumentType::Deed,
+        &true,
+    );
+    t.doc_client.link_to_vault(&doc_id, &vault_id);
+
+    // ── 1. Low liveness score blocks the transfer ───────────────────────
+    let sig = BytesN::from_array(&t.env, &[0u8; 64]);
+    t.pol_client.submit_verification(
+        &owner,
+        &5_000u32,
+        &VerificationSource::BehaviorPattern,
+        &sig,
+    );
+    let unhealthy = t.vault_client.try_transfer_ownership(&vault_id, &new_wallet);
+    assert_eq!(unhealthy.err().unwrap().unwrap(), VaultError::OwnerUnhealthy);
+    t.pol_client.emergency_checkin(&owner);
+
+    // ── 2. Propose, then accept from the new wallet ─────────────────────
+    // Every open vault the owner holds moves with the PoL model; one that
+    // is not Active blocks the move until the owner brings it back
+    let other_vault = t.vault_client.create_vault(&owner, &token_addr);
+    let closed_vault = t.vault_client.create_vault(&owner, &token_addr);
+    t.vault_client.close_vault(&closed_vault);
+    t.vault_client.transition_status(&other_vault, &t.admin, &VaultStatus::Alert);
+    t.vault_client.transfer_ownership(&vault_id, &new_wallet);
+    let stranded = t.vault_client.try_accept_ownership(&vault_id);
+    assert_eq!(stranded.err().unwrap().unwrap(), VaultError::InvalidStatus);
+    t.vault_client.transition_status(&other_vault, &t.admin, &VaultStatus::Active);
+
+    assert_eq!(t.vault_client.get_vault(&vault_id).owner, owner);
+    assert_eq!(t.vault_client.get_pending_owner(&vault_id), Some(new_wallet.clone()));
+
+    t.vault_client.accept_ownership(&vault_id);
+    assert_eq!(t.vault_client.get_vault(&vault_id).owner, new_wallet);
+    assert_eq!(t.vault_client.get_vault(&other_vault).owner, new_wallet);
+    assert_eq!(t.vault_client.get_pending_owner(&vault_id), None);
+    assert_eq!(t.vault_client.get_vaults_by_owner(&owner), Vec::from_array(&t.env, [closed_vault]));
+    assert_eq!(
+        t.vault_client.get_vaults_by_owner(&new_wallet),
+        Vec::from_array(&t.env, [vault_id, other_vault])
+    );
+
+    // ── 3. PoL history and documents followed the vault ─────────────────
+    assert!(t.pol_client.try_get_model(&owner).is_err());
+    assert_eq!(t.pol_client.get_model(&new_wallet).total_verifications, 1);
+    assert_eq!(t.pol_client.get_liveness_score(&new_wallet), 10_000);
+    assert_eq!(t.doc_client.get_document(&doc_id).owner, new_wallet);
+
+    // The new owner controls the funds; a second accept has nothing to do
+    t.vault_client.withdraw(&vault_id, &token_addr, &new_wallet, &1_000);
+    assert!(t.vault_client.try_accept_ownership(&vault_id).is_err());
+}
+
+// ===========================================================================
+// Test 8 - Emergency Pause Across All Contracts
+// ===========================================================================
+
+#[test]
+fn test_global_pause_blocks_writes_not_reads() {
+    let t = setup_full_env();
+
+    let owner = Address::generate(&t.env);
+    let token_addr = Address::generate(&t.env);
+    let vault_id = t.vault_client.create_vault(&owner, &token_addr);
+    t.pol_client
+        .register_model(&owner, &create_weights(&t.env), &100_000i128);
+
+    t.vault_client.pause();
+    t.pol_client.pause();
+    t.benef_client.pause();
+    t.doc_client.pause();
+    assert!(t.vault_client.is_paused());
+
+    // ── 1. Writes are rejected everywhere ───────────────────────────────
+    assert!(t.vault_client.try_create_vault(&owner, &token_addr).is_err());
+    let sig = BytesN::from_array(&t.env, &[0u8; 64]);
+    assert!(t
+        .pol_client
+        .try_submit_verification(&owner, &1_000u32, &VerificationSource::BehaviorPattern, &sig)
+        .is_err());
+    assert!(t.benef_client.try_record_claim(&vault_id, &owner).is_err());
+    assert!(t
+        .doc_client
+        .try_register_document(
+            &owner,
+            &String::from_str(&t.env, "QmPaused"),
+            &BytesN::from_array(&t.env, &[8u8; 32]),
+            &DocumentType::Will,
+            &true,
+        )
+        .is_err());
+
+    // Including owner removals, fee collection and beneficiary key retrieval
+    let paused = t.vault_client.try_remove_allowance(&vault_id, &owner, &token_addr);
+    assert_eq!(paused.err().unwrap().unwrap(), VaultError::Paused);
+    let paused = t.vault_client.try_remove_withdrawal_address(&vault_id, &owner);
+    assert_eq!(paused.err().unwrap().unwrap(), VaultError::Paused);
+    let paused = t.vault_client.try_collect_fees(&token_addr, &t.admin);
+    assert_eq!(paused.err().unwrap().unwrap(), VaultError::Paused);
+    let paused = t.doc_client.try_get_document_access(&0, &owner);
+    assert_eq!(paused.err().unwrap().unwrap(), DocumentError::Paused);
+
+    // ── 2. Reads keep working ───────────────────────────────────────────
+    assert_eq!(t.vault_client.get_status(&vault_id), VaultStatus::Active);
+    assert_eq!(t.pol_client.get_liveness_score(&owner), 10_000);
+    assert_eq!(t.benef_client.get_beneficiaries(&vault_id).len(), 0);
+    assert_eq!(t.doc_client.get_vault_documents(&vault_id).len(), 0);
+
+    // ── 3. Unpausing restores writes ────────────────────────────────────
+    t.vault_client.unpause();
+    t.pol_client.unpause();
+    t.vault_client.create_vault(&owner, &token_addr);
+    t.pol_client
+        .submit_verification(&owner, &9_000u32, &VerificationSource::BehaviorPattern, &sig);
+    assert_eq!(t.pol_client.get_liveness_score(&owner), 9_000);
+}
+
+// ===========================================================================
+// Test 9 - Hidden Beneficiary Reveals Itself to Read Documents
+// ===========================================================================
+
+#[test]
+fn test_committed_beneficiary_document_access() {
+    let t = setup_full_env();
+
+    let owner = Address::generate(&t.env);
+    let heir = Address::generate(&t.env);
+    let token_addr = Address::generate(&t.env);
+    let salt = BytesN::from_array(&t.env, &[5u8; 32]);
+
+    // ── 1. Vault whose only heir is a hashed commitment ─────────────────
+    let vault_id = t.vault_client.create_vault(&owner, &token_addr);
+    let mut commitments = Vec::new(&t.env);
+    commitments.push_back(BeneficiaryCommitment {
+        commitment: t.vault_client.compute_commitment(&heir, &salt),
+        percentage: 10_000,
+        claimed: false,
+    });
+    t.vault_client.set_beneficiary_commitments(&vault_id, &commitments);
+
+    let doc_id = t.doc_client.register_document(
+        &owner,
+        &String::from_str(&t.env, "QmHiddenHeirWill"),
+        &BytesN::from_array(&t.env, &[6u8; 32]),
+        &DocumentType::Will,
+        &true,
+    );
+    t.doc_client.link_to_vault(&doc_id, &vault_id);
+    let wrapped_key = Bytes::from_slice(&t.env, &[42u8; 32]);
+    t.doc_client.store_encrypted_key(&doc_id, &heir, &wrapped_key);
+
+    t.vault_client
+        .transition_status(&vault_id, &t.admin, &VaultStatus::Triggered);
+
+    // ── 2. Without the salt the heir is not on any list ─────────────────
+    let hidden = t.doc_client.try_get_document_access(&doc_id, &heir);
+    assert_eq!(hidden.err().unwrap().unwrap(), DocumentError::NotABeneficiary);
+    let wrong_salt = BytesN::from_array(&t.env, &[6u8; 32]);
+    let wrong = t.doc_client.try_get_document_access_committed(&doc_id, &heir, &wrong_salt);
+    assert_eq!(wrong.err().unwrap().unwrap(), DocumentError::NotABeneficiary);
+
+    // ── 3. Revealing address and salt opens the document ────────────────
+    let access = t.doc_client.get_document_access_committed(&doc_id, &heir, &salt);
+    assert_eq!(access.encrypted_key, wrapped_key);
+    assert!(t.doc_client.has_access(&doc_id, &heir));
+}
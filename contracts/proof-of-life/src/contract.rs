@@ -27,6 +27,35 @@ impl ProofOfLifeContract {
         Ok(())
     }
 
+    /// Pause every state-changing call. Reads keep working.
+    pub fn pause(env: Env) -> Result<(), ProofOfLifeError> {
+        let admin = storage::get_admin(&env);
+        admin.require_auth();
+        storage::set_paused(&env, true);
+
+        env.events()
+            .publish((symbol_short!("pol"), symbol_short!("paused")), admin);
+
+        Ok(())
+    }
+
+    /// Lift a pause set by `pause`.
+    pub fn unpause(env: Env) -> Result<(), ProofOfLifeError> {
+        let admin = storage::get_admin(&env);
+        admin.require_auth();
+        storage::set_paused(&env, false);
+
+        env.events()
+            .publish((symbol_short!("pol"), symbol_short!("unpaused")), admin);
+
+        Ok(())
+    }
+
+    /// Whether the contract is paused.
+    pub fn is_paused(env: Env) -> bool {
+        storage::is_paused(&env)
+    }
+
     /// Register a perceptron model for a user.
     pub fn register_model(
         env: Env,
@@ -34,6 +63,8 @@ impl ProofOfLifeContract {
         initial_weights: Vec<i128>,
         bias: i128,
     ) -> Result<(), ProofOfLifeError> {
+        ensure_not_paused(&env)?;
+
         user.require_auth();
 
         if initial_weights.len() != EXPECTED_WEIGHTS_COUNT {
@@ -69,6 +100,8 @@ impl ProofOfLifeContract {
         source: VerificationSource,
         oracle_sig: BytesN<64>,
     ) -> Result<(), ProofOfLifeError> {
+        ensure_not_paused(&env)?;
+
         let oracle = storage::get_oracle(&env);
         oracle.require_auth();
 
@@ -126,6 +159,8 @@ impl ProofOfLifeContract {
         new_weights: Vec<i128>,
         new_bias: i128,
     ) -> Result<(), ProofOfLifeError> {
+        ensure_not_paused(&env)?;
+
         user.require_auth();
 
         if new_weights.len() != EXPECTED_WEIGHTS_COUNT {
@@ -155,6 +190,8 @@ impl ProofOfLifeContract {
 
     /// Emergency check-in. Resets score to maximum.
     pub fn emergency_checkin(env: Env, user: Address) -> Result<(), ProofOfLifeError> {
+        ensure_not_paused(&env)?;
+
         user.require_auth();
 
         if storage::get_model(&env, &user).is_none() {
@@ -169,11 +206,54 @@ impl ProofOfLifeContract {
         Ok(())
     }
 
+    /// Whether the user's last score is at or above their alert threshold.
+    pub fn is_healthy(env: Env, user: Address) -> Result<bool, ProofOfLifeError> {
+        let model = storage::get_model(&env, &user)
+            .ok_or(ProofOfLifeError::UserNotRegistered)?;
+        Ok(storage::get_last_score(&env, &user) >= model.alert_threshold)
+    }
+
+    /// Move a user's model, score history and vault link to a new address.
+    /// Only callable by the vault contract the user is linked to, as part of
+    /// the transfer of the linked vault `vault_id`.
+    pub fn migrate_user(
+        env: Env,
+        old_user: Address,
+        new_user: Address,
+        vault_id: u64,
+    ) -> Result<(), ProofOfLifeError> {
+        ensure_not_paused(&env)?;
+
+        let vault_contract = storage::get_linked_vault(&env, &old_user)
+            .ok_or(ProofOfLifeError::VaultNotLinked)?;
+        vault_contract.require_auth();
+
+        if storage::get_linked_vault_id(&env, &old_user) != Some(vault_id) {
+            return Err(ProofOfLifeError::VaultMismatch);
+        }
+
+        if storage::get_model(&env, &new_user).is_some() {
+            return Err(ProofOfLifeError::AlreadyRegistered);
+        }
+
+        storage::migrate_user(&env, &old_user, &new_user);
+
+        env.events()
+            .publish((symbol_short!("pol"), symbol_short!("migrate")), (&old_user, &new_user));
+
+        Ok(())
+    }
+
     /// Get model data for a user.
     pub fn get_model(env: Env, user: Address) -> Result<LifeModel, ProofOfLifeError> {
         storage::get_model(&env, &user).ok_or(ProofOfLifeError::UserNotRegistered)
     }
 
+    /// Get the vault ID a user's model is linked to, if any.
+    pub fn get_linked_vault_id(env: Env, user: Address) -> Option<u64> {
+        storage::get_linked_vault_id(&env, &user)
+    }
+
     /// Link a vault contract to this user.
     pub fn link_vault(
         env: Env,
@@ -181,9 +261,19 @@ impl ProofOfLifeContract {
         vault_contract: Address,
         vault_id: u64,
     ) -> Result<(), ProofOfLifeError> {
+        ensure_not_paused(&env)?;
+
         user.require_auth();
         storage::set_linked_vault(&env, &user, &vault_contract);
         storage::set_linked_vault_id(&env, &user, vault_id);
         Ok(())
     }
 }
+
+/// Reject state-changing calls while the admin has paused the contract.
+fn ensure_not_paused(env: &Env) -> Result<(), ProofOfLifeError> {
+    if storage::is_paused(env) {
+        return Err(ProofOfLifeError::Paused);
+    }
+    Ok(())
+}
@@ -11,4 +11,7 @@ pub enum ProofOfLifeError {
     AlreadyInitialized = 6,
     NotInitialized = 7,
     VaultNotLinked = 8,
+    AlreadyRegistered = 9,
+    Paused = 10,
+    VaultMismatch = 11,
 }
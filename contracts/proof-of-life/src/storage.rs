@@ -13,6 +13,7 @@ pub enum DataKey {
     LinkedVault(Address),
     LinkedVaultId(Address),
     Initialized,
+    Paused,
 }
 
 pub fn is_initialized(env: &Env) -> bool {
@@ -23,6 +24,14 @@ pub fn set_initialized(env: &Env) {
     env.storage().instance().set(&DataKey::Initialized, &true);
 }
 
+pub fn is_paused(env: &Env) -> bool {
+    env.storage().instance().get(&DataKey::Paused).unwrap_or(false)
+}
+
+pub fn set_paused(env: &Env, paused: bool) {
+    env.storage().instance().set(&DataKey::Paused, &paused);
+}
+
 pub fn get_admin(env: &Env) -> Address {
     env.storage().instance().get(&DataKey::Admin).unwrap()
 }
@@ -90,3 +99,21 @@ pub fn set_linked_vault_id(env: &Env, user: &Address, vault_id: u64) {
         .persistent()
         .set(&DataKey::LinkedVaultId(user.clone()), &vault_id);
 }
+
+/// Move every per-user entry from `from` to `to`.
+pub fn migrate_user(env: &Env, from: &Address, to: &Address) {
+    let storage = env.storage().persistent();
+    let keys = [
+        (DataKey::UserModel(from.clone()), DataKey::UserModel(to.clone())),
+        (DataKey::LastScore(from.clone()), DataKey::LastScore(to.clone())),
+        (DataKey::Verifications(from.clone()), DataKey::Verifications(to.clone())),
+        (DataKey::LinkedVault(from.clone()), DataKey::LinkedVault(to.clone())),
+        (DataKey::LinkedVaultId(from.clone()), DataKey::LinkedVaultId(to.clone())),
+    ];
+    for (old, new) in keys {
+        if let Some(value) = storage.get::<_, soroban_sdk::Val>(&old) {
+            storage.set(&new, &value);
+            storage.remove(&old);
+        }
+    }
+}
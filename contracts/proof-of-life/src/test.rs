@@ -263,7 +263,9 @@ fn test_link_vault() {
     let vault_contract = Address::generate(&env);
     let vault_id: u64 = 42;
 
+    assert_eq!(client.get_linked_vault_id(&user), None);
     client.link_vault(&user, &vault_contract, &vault_id);
+    assert_eq!(client.get_linked_vault_id(&user), Some(vault_id));
 
     // Verify the vault is linked by submitting a verification that will
     // trigger the transition event (proving vault link was stored).
@@ -287,3 +289,33 @@ fn test_get_liveness_score_default() {
     let score = client.get_liveness_score(&user);
     assert_eq!(score, 10_000);
 }
+
+// ─── 14. test_migrate_user ──────────────────────────────────────────
+
+#[test]
+fn test_migrate_user() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _admin, _oracle, user) = setup_with_user(&env);
+    let new_user = Address::generate(&env);
+
+    // Only a user linked to a vault can be migrated
+    let result = client.try_migrate_user(&user, &new_user, &1u64);
+    assert_eq!(result, Err(Ok(ProofOfLifeError::VaultNotLinked)));
+
+    let vault_contract = Address::generate(&env);
+    client.link_vault(&user, &vault_contract, &1u64);
+    let sig = BytesN::from_array(&env, &[0u8; 64]);
+    client.submit_verification(&user, &6_000u32, &VerificationSource::ManualCheckin, &sig);
+    assert!(!client.is_healthy(&user));
+
+    // Only the transfer of the linked vault may take the model along
+    let result = client.try_migrate_user(&user, &new_user, &2u64);
+    assert_eq!(result, Err(Ok(ProofOfLifeError::VaultMismatch)));
+
+    client.migrate_user(&user, &new_user, &1u64);
+    assert_eq!(client.get_liveness_score(&new_user), 6_000);
+    assert_eq!(client.get_model(&new_user).total_verifications, 1);
+    assert!(client.try_get_model(&user).is_err());
+}
@@ -1,8 +1,38 @@
-use soroban_sdk::{contract, contractimpl, symbol_short, token, Address, Env, Vec};
+use soroban_sdk::xdr::ToXdr;
+use soroban_sdk::{contract, contractimpl, symbol_short, token, Address, BytesN, Env, Map, Vec};
 
 use crate::errors::VaultError;
+use crate::proof_of_life::ProofOfLifeClient;
+use crate::registry::DocumentRegistryClient;
 use crate::storage;
-use crate::types::{Beneficiary, VaultId, VaultInfo, VaultStatus};
+use crate::strategy::StrategyClient;
+use crate::types::{
+    Allowance, BalanceReport, Bequest, BequestKind, Beneficiary, BeneficiaryCommitment, CreditorClaim,
+    DepositConfig, Dispute, DisputeConfig, DisputeRuling, DisputeStatus, Evidence, ExecutorFee,
+    FeeConfig, Payout, PendingBeneficiaries, PendingGuardians, PendingWaterfall, PendingWithdrawalLimit, TokenBalance, WaterfallChange, VaultId, VaultInfo, VaultStatus,
+    WithdrawalAddress, WithdrawalLimit, WithdrawalRecord,
+};
+
+const MAX_TOKENS: u32 = 10;
+/// Most vault IDs `list_vaults` scans in one page.
+const MAX_PAGE_SIZE: u32 = 50;
+const MAX_BEQUESTS: u32 = 20;
+const MAX_CREDITORS: u32 = 20;
+const MAX_EXECUTOR_FEE_BPS: u32 = 500;
+/// How long after the trigger the executor and creditors have to be paid
+/// before their unpaid claims lapse and the heirs may proceed.
+const PRIORITY_CLAIM_WINDOW: u64 = 180 * 24 * 60 * 60;
+const MAX_CONTRIBUTORS: u32 = 50;
+const MAX_WITHDRAWAL_ADDRESSES: u32 = 20;
+const WITHDRAWAL_ADDRESS_DELAY: u64 = 2 * 24 * 60 * 60;
+const WITHDRAWAL_WINDOW: u64 = 24 * 60 * 60;
+const MAX_FREEZE_DURATION: u64 = 30 * 24 * 60 * 60;
+const MAX_DISPUTE_WINDOW: u64 = 90 * 24 * 60 * 60;
+/// How long the resolver has to rule on a dispute before the trigger stands.
+const DISPUTE_RESOLUTION_WINDOW: u64 = 30 * 24 * 60 * 60;
+const MAX_EVIDENCE: u32 = 20;
+const MAX_PLATFORM_FEE_BPS: u32 = 300;
+const MAX_ALLOWANCES: u32 = 10;
 
 #[contract]
 pub struct VaultContract;
@@ -20,23 +50,57 @@ impl VaultContract {
         Ok(())
     }
 
-    /// Create a new vault. Returns the vault ID.
+    /// Pause every state-changing call. Reads keep working.
+    pub fn pause(env: Env) -> Result<(), VaultError> {
+        let admin = storage::get_admin(&env);
+        admin.require_auth();
+        storage::set_paused(&env, true);
+
+        env.events()
+            .publish((symbol_short!("vault"), symbol_short!("paused")), admin);
+
+        Ok(())
+    }
+
+    /// Lift a pause set by `pause`.
+    pub fn unpause(env: Env) -> Result<(), VaultError> {
+        let admin = storage::get_admin(&env);
+        admin.require_auth();
+        storage::set_paused(&env, false);
+
+        env.events()
+            .publish((symbol_short!("vault"), symbol_short!("unpaused")), admin);
+
+        Ok(())
+    }
+
+    /// Whether the contract is paused.
+    pub fn is_paused(env: Env) -> bool {
+        storage::is_paused(&env)
+    }
+
+    /// Create a new vault. `token` is registered as the vault's first asset.
+    /// Returns the vault ID.
     pub fn create_vault(env: Env, owner: Address, token: Address) -> Result<VaultId, VaultError> {
+        ensure_not_paused(&env)?;
+
         owner.require_auth();
 
         let vault_id = storage::get_vault_count(&env);
         let vault = VaultInfo {
             id: vault_id,
             owner: owner.clone(),
-            token,
+            token: token.clone(),
             status: VaultStatus::Active,
-            balance: 0,
             created_at: env.ledger().timestamp(),
             last_updated: env.ledger().timestamp(),
         };
 
         storage::set_vault(&env, vault_id, &vault);
         storage::set_vault_count(&env, vault_id + 1);
+        storage::set_vault_tokens(&env, vault_id, &Vec::from_array(&env, [token]));
+        storage::set_vault_fees(&env, vault_id, &storage::get_fee_config(&env));
+        storage::add_owner_vault(&env, &owner, vault_id);
 
         env.events()
             .publish((symbol_short!("vault"), symbol_short!("created")), vault_id);
@@ -44,8 +108,18 @@ impl VaultContract {
         Ok(vault_id)
     }
 
-    /// Deposit tokens into a vault.
-    pub fn deposit(env: Env, vault_id: VaultId, from: Address, amount: i128) -> Result<(), VaultError> {
+    /// Deposit tokens into a vault. A vault holds at most `MAX_TOKENS` assets,
+    /// and only the owner may add a new one; third parties deposit into
+    /// tokens the vault already holds.
+    pub fn deposit(
+        env: Env,
+        vault_id: VaultId,
+        token: Address,
+        from: Address,
+        amount: i128,
+    ) -> Result<(), VaultError> {
+        ensure_writable(&env, vault_id)?;
+
         if amount <= 0 {
             return Err(VaultError::ZeroAmount);
         }
@@ -55,25 +129,137 @@ impl VaultContract {
         let mut vault = storage::get_vault(&env, vault_id)
             .ok_or(VaultError::VaultNotFound)?;
 
-        if vault.owner != from {
-            return Err(VaultError::NotAuthorized);
+        if vault.status == VaultStatus::Closed {
+            return Err(VaultError::InvalidStatus);
         }
 
-        let token_client = token::Client::new(&env, &vault.token);
-        token_client.transfer(&from, &env.current_contract_address(), &amount);
+        let is_owner = owners_of(&env, &vault).contains(&from);
+        if !is_owner {
+            let config = storage::get_deposit_config(&env, vault_id);
+            if !config.open && !config.allowlist.contains(&from) {
+                return Err(VaultError::NotAuthorized);
+            }
+        }
+
+        // Owners and allowlisted depositors are never locked out; only
+        // strangers depositing in open mode count against the cap.
+        let mut contributors = storage::get_contributors(&env, vault_id);
+        if !contributors.contains(&from) {
+            let is_stranger = !is_owner
+                && !storage::get_deposit_config(&env, vault_id).allowlist.contains(&from);
+            if is_stranger && contributors.len() >= MAX_CONTRIBUTORS {
+                return Err(VaultError::TooManyContributors);
+            }
+            contributors.push_back(from.clone());
+            storage::set_contributors(&env, vault_id, &contributors);
+        }
+
+        let mut tokens = storage::get_vault_tokens(&env, vault_id);
+        if !tokens.contains(&token) {
+            if !is_owner {
+                return Err(VaultError::NotAuthorized);
+            }
+            if tokens.len() >= MAX_TOKENS {
+                return Err(VaultError::TooManyTokens);
+            }
+            tokens.push_back(token.clone());
+            storage::set_vault_tokens(&env, vault_id, &tokens);
+        }
+
+        let token_client = token::Client::new(&env, &token);
+        token_client.transfer(&from, env.current_contract_address(), &amount);
+
+        let fee = amount * storage::get_vault_fees(&env, vault_id).deposit_bps as i128 / 10_000;
+        if fee > 0 {
+            accrue_fee(&env, vault_id, &token, fee);
+        }
+        let credited = amount - fee;
 
-        vault.balance += amount;
+        storage::increase_balance(&env, vault_id, &token, credited);
+        let total = storage::get_contribution(&env, vault_id, &from, &token) + credited;
+        storage::set_contribution(&env, vault_id, &from, &token, total);
         vault.last_updated = env.ledger().timestamp();
         storage::set_vault(&env, vault_id, &vault);
 
         env.events()
-            .publish((symbol_short!("vault"), symbol_short!("deposit")), (vault_id, amount));
+            .publish((symbol_short!("vault"), symbol_short!("deposit")), (vault_id, token.clone(), credited));
+        env.events()
+            .publish((symbol_short!("vault"), symbol_short!("contrib")), (vault_id, from, token, total));
+
+        Ok(())
+    }
+
+    /// Control who besides the owner may deposit, and whether closing the
+    /// vault refunds other contributors first.
+    pub fn set_deposit_config(
+        env: Env,
+        vault_id: VaultId,
+        config: DepositConfig,
+    ) -> Result<(), VaultError> {
+        ensure_writable(&env, vault_id)?;
+
+        let vault = storage::get_vault(&env, vault_id)
+            .ok_or(VaultError::VaultNotFound)?;
+
+        vault.owner.require_auth();
+
+        if vault.status != VaultStatus::Active {
+            return Err(VaultError::InvalidStatus);
+        }
+
+        if config.allowlist.len() > MAX_CONTRIBUTORS {
+            return Err(VaultError::TooManyContributors);
+        }
+
+        storage::set_deposit_config(&env, vault_id, &config);
+
+        env.events()
+            .publish((symbol_short!("vault"), symbol_short!("dep_cfg")), (vault_id, config.open));
 
         Ok(())
     }
 
-    /// Withdraw tokens from a vault. Only allowed when status is Active.
-    pub fn withdraw(env: Env, vault_id: VaultId, to: Address, amount: i128) -> Result<(), VaultError> {
+    /// Get the deposit configuration for a vault.
+    pub fn get_deposit_config(env: Env, vault_id: VaultId) -> Result<DepositConfig, VaultError> {
+        if storage::get_vault(&env, vault_id).is_none() {
+            return Err(VaultError::VaultNotFound);
+        }
+        Ok(storage::get_deposit_config(&env, vault_id))
+    }
+
+    /// Everyone who has deposited into a vault, the owner included.
+    pub fn get_contributors(env: Env, vault_id: VaultId) -> Result<Vec<Address>, VaultError> {
+        if storage::get_vault(&env, vault_id).is_none() {
+            return Err(VaultError::VaultNotFound);
+        }
+        Ok(storage::get_contributors(&env, vault_id))
+    }
+
+    /// Total amount of `token` deposited by `contributor`.
+    pub fn get_contribution(
+        env: Env,
+        vault_id: VaultId,
+        contributor: Address,
+        token: Address,
+    ) -> Result<i128, VaultError> {
+        if storage::get_vault(&env, vault_id).is_none() {
+            return Err(VaultError::VaultNotFound);
+        }
+        Ok(storage::get_contribution(&env, vault_id, &contributor, &token))
+    }
+
+    /// Withdraw tokens from a vault. Allowed when Active, or in Alert up to
+    /// the token's reduced `alert_limit`. Once an allowlist is configured,
+    /// destinations other than the owner must be active entries on it.
+    pub fn withdraw(
+        env: Env,
+        vault_id: VaultId,
+        token: Address,
+        to: Address,
+        amount: i128,
+    ) -> Result<(), VaultError> {
+        ensure_writable(&env, vault_id)?;
+
         if amount <= 0 {
             return Err(VaultError::ZeroAmount);
         }
@@ -83,33 +269,51 @@ impl VaultContract {
 
         vault.owner.require_auth();
 
-        if vault.status != VaultStatus::Active {
-            return Err(VaultError::InvalidStatus);
+        let limit = current_withdrawal_limit(&env, vault_id, &token);
+        let cap = match (&vault.status, limit) {
+            (VaultStatus::Active, limit) => limit.map(|l| l.daily_limit),
+            (VaultStatus::Alert, Some(l)) if l.alert_limit > 0 => Some(l.alert_limit),
+            _ => return Err(VaultError::InvalidStatus),
+        };
+
+        ensure_destination_allowed(&env, &vault, &to)?;
+
+        // Withdrawals beyond the idle funds recall the strategy position first.
+        let idle = storage::get_balance(&env, vault_id, &token) - storage::get_invested(&env, vault_id, &token);
+        if idle < amount {
+            recall(&env, vault_id, &token)?;
         }
 
-        if vault.balance < amount {
+        if storage::get_balance(&env, vault_id, &token) < amount {
             return Err(VaultError::InsufficientBalance);
         }
 
-        let token_client = token::Client::new(&env, &vault.token);
+        if let Some(cap) = cap {
+            record_withdrawal(&env, vault_id, &token, amount, cap)?;
+        }
+
+        let token_client = token::Client::new(&env, &token);
         token_client.transfer(&env.current_contract_address(), &to, &amount);
 
-        vault.balance -= amount;
+        storage::decrease_balance(&env, vault_id, &token, amount);
         vault.last_updated = env.ledger().timestamp();
         storage::set_vault(&env, vault_id, &vault);
 
         env.events()
-            .publish((symbol_short!("vault"), symbol_short!("withdraw")), (vault_id, amount));
+            .publish((symbol_short!("vault"), symbol_short!("withdraw")), (vault_id, token, amount));
 
         Ok(())
     }
 
     /// Set beneficiaries for a vault. Sum of percentages must equal 10000.
+    /// Replacing an existing list is queued behind the beneficiary delay.
     pub fn set_beneficiaries(
         env: Env,
         vault_id: VaultId,
         beneficiaries: Vec<Beneficiary>,
     ) -> Result<(), VaultError> {
+        ensure_writable(&env, vault_id)?;
+
         let vault = storage::get_vault(&env, vault_id)
             .ok_or(VaultError::VaultNotFound)?;
 
@@ -131,86 +335,2750 @@ impl VaultContract {
             return Err(VaultError::PercentageSumInvalid);
         }
 
-        storage::set_beneficiaries(&env, vault_id, &beneficiaries);
+        queue_or_apply(&env, vault_id, beneficiaries, Vec::new(&env));
+
+        Ok(())
+    }
+
+    /// Check whether an address is on a vault's plain beneficiary list.
+    pub fn is_beneficiary(env: Env, vault_id: VaultId, address: Address) -> bool {
+        storage::get_beneficiaries(&env, vault_id)
+            .iter()
+            .any(|b| b.address == address)
+    }
+
+    /// Check whether `address` and `salt` open one of a vault's beneficiary
+    /// commitments. Lets other contracts recognise hidden beneficiaries once
+    /// they reveal themselves.
+    pub fn is_committed_beneficiary(env: Env, vault_id: VaultId, address: Address, salt: BytesN<32>) -> bool {
+        let commitment = commitment_of(&env, &address, &salt);
+        storage::get_commitments(&env, vault_id)
+            .iter()
+            .any(|c| c.commitment == commitment)
+    }
+
+    /// Set hidden beneficiaries as `sha256(address_xdr || salt)` commitments.
+    /// Replaces any plain beneficiary list. Sum of percentages must equal 10000.
+    /// Replacing an existing configuration is queued behind the beneficiary delay.
+    pub fn set_beneficiary_commitments(
+        env: Env,
+        vault_id: VaultId,
+        commitments: Vec<BeneficiaryCommitment>,
+    ) -> Result<(), VaultError> {
+        ensure_writable(&env, vault_id)?;
+
+        let vault = storage::get_vault(&env, vault_id)
+            .ok_or(VaultError::VaultNotFound)?;
+
+        vault.owner.require_auth();
+
+        if vault.status != VaultStatus::Active {
+            return Err(VaultError::InvalidStatus);
+        }
+
+        if commitments.is_empty() {
+            return Err(VaultError::NoBeneficiaries);
+        }
+
+        let mut sum: u32 = 0;
+        for c in commitments.iter() {
+            sum += c.percentage;
+        }
+        if sum != 10_000 {
+            return Err(VaultError::PercentageSumInvalid);
+        }
+
+        queue_or_apply(&env, vault_id, Vec::new(&env), commitments);
+
+        Ok(())
+    }
+
+    /// Apply a queued beneficiary change once its delay has elapsed.
+    /// Only possible while the vault is still Active.
+    pub fn execute_pending_beneficiaries(env: Env, vault_id: VaultId) -> Result<(), VaultError> {
+        ensure_writable(&env, vault_id)?;
+
+        let vault = storage::get_vault(&env, vault_id)
+            .ok_or(VaultError::VaultNotFound)?;
+
+        vault.owner.require_auth();
+
+        if vault.status != VaultStatus::Active {
+            return Err(VaultError::InvalidStatus);
+        }
+
+        let pending = storage::get_pending_beneficiaries(&env, vault_id)
+            .ok_or(VaultError::NoPendingChange)?;
+
+        if env.ledger().timestamp() < pending.executable_at {
+            return Err(VaultError::TimelockActive);
+        }
+
+        storage::remove_pending_beneficiaries(&env, vault_id);
+        apply_beneficiaries(&env, vault_id, pending.beneficiaries, pending.commitments);
+
+        Ok(())
+    }
+
+    /// Cancel a queued beneficiary change. Callable by the owner or a guardian.
+    pub fn cancel_pending_beneficiaries(
+        env: Env,
+        vault_id: VaultId,
+        caller: Address,
+    ) -> Result<(), VaultError> {
+        caller.require_auth();
+
+        let vault = storage::get_vault(&env, vault_id)
+            .ok_or(VaultError::VaultNotFound)?;
+
+        let is_guardian = storage::get_guardians(&env, vault_id).contains(&caller);
+        if caller != vault.owner && !is_guardian {
+            return Err(VaultError::NotAuthorized);
+        }
+
+        if storage::get_pending_beneficiaries(&env, vault_id).is_none() {
+            return Err(VaultError::NoPendingChange);
+        }
+        storage::remove_pending_beneficiaries(&env, vault_id);
 
         env.events()
-            .publish((symbol_short!("vault"), symbol_short!("benef")), vault_id);
+            .publish((symbol_short!("vault"), symbol_short!("bcancel")), (vault_id, caller));
 
         Ok(())
     }
 
-    /// Get vault info.
-    pub fn get_vault(env: Env, vault_id: VaultId) -> Result<VaultInfo, VaultError> {
-        storage::get_vault(&env, vault_id).ok_or(VaultError::VaultNotFound)
+    /// Get the queued beneficiary change for a vault, if any.
+    pub fn get_pending_beneficiaries(
+        env: Env,
+        vault_id: VaultId,
+    ) -> Result<Option<PendingBeneficiaries>, VaultError> {
+        if storage::get_vault(&env, vault_id).is_none() {
+            return Err(VaultError::VaultNotFound);
+        }
+        Ok(storage::get_pending_beneficiaries(&env, vault_id))
     }
 
-    /// Get vault status.
-    pub fn get_status(env: Env, vault_id: VaultId) -> Result<VaultStatus, VaultError> {
+    /// Apply every queued waterfall change whose delay has elapsed. Only
+    /// possible while the vault is still Active.
+    pub fn execute_pending_waterfall(env: Env, vault_id: VaultId) -> Result<(), VaultError> {
+        ensure_writable(&env, vault_id)?;
+
         let vault = storage::get_vault(&env, vault_id)
             .ok_or(VaultError::VaultNotFound)?;
-        Ok(vault.status)
+
+        vault.owner.require_auth();
+
+        if vault.status != VaultStatus::Active {
+            return Err(VaultError::InvalidStatus);
+        }
+
+        let pending = storage::get_pending_waterfall(&env, vault_id);
+        if pending.is_empty() {
+            return Err(VaultError::NoPendingChange);
+        }
+
+        let now = env.ledger().timestamp();
+        let mut waiting = Vec::new(&env);
+        for p in pending.iter() {
+            if now < p.executable_at {
+                waiting.push_back(p);
+            } else {
+                apply_waterfall(&env, vault_id, p.change);
+            }
+        }
+        if waiting.len() == pending.len() {
+            return Err(VaultError::TimelockActive);
+        }
+        storage::set_pending_waterfall(&env, vault_id, &waiting);
+
+        Ok(())
     }
 
-    /// Get vault balance.
-    pub fn get_balance(env: Env, vault_id: VaultId) -> Result<i128, VaultError> {
+    /// Cancel every queued waterfall change. Callable by the owner or a guardian.
+    pub fn cancel_pending_waterfall(
+        env: Env,
+        vault_id: VaultId,
+        caller: Address,
+    ) -> Result<(), VaultError> {
+        caller.require_auth();
+
         let vault = storage::get_vault(&env, vault_id)
             .ok_or(VaultError::VaultNotFound)?;
-        Ok(vault.balance)
+
+        let is_guardian = storage::get_guardians(&env, vault_id).contains(&caller);
+        if caller != vault.owner && !is_guardian {
+            return Err(VaultError::NotAuthorized);
+        }
+
+        if storage::get_pending_waterfall(&env, vault_id).is_empty() {
+            return Err(VaultError::NoPendingChange);
+        }
+        storage::remove_pending_waterfall(&env, vault_id);
+
+        env.events()
+            .publish((symbol_short!("vault"), symbol_short!("wcancel")), (vault_id, caller));
+
+        Ok(())
     }
 
-    /// Get beneficiaries for a vault.
-    pub fn get_beneficiaries(env: Env, vault_id: VaultId) -> Result<Vec<Beneficiary>, VaultError> {
+    /// Get the queued waterfall changes for a vault.
+    pub fn get_pending_waterfall(env: Env, vault_id: VaultId) -> Result<Vec<PendingWaterfall>, VaultError> {
         if storage::get_vault(&env, vault_id).is_none() {
             return Err(VaultError::VaultNotFound);
         }
-        Ok(storage::get_beneficiaries(&env, vault_id))
+        Ok(storage::get_pending_waterfall(&env, vault_id))
     }
 
-    /// Link a ProofOfLife contract to this vault.
-    pub fn link_proof_of_life(
+    /// Set the guardians allowed to cancel pending beneficiary changes.
+    /// Adding guardians takes effect at once; a change that drops any current
+    /// guardian is queued behind the beneficiary delay, so the guardians it
+    /// removes can still cancel it or any change queued alongside it.
+    pub fn set_guardians(
         env: Env,
         vault_id: VaultId,
-        pol_contract: Address,
+        guardians: Vec<Address>,
     ) -> Result<(), VaultError> {
+        ensure_writable(&env, vault_id)?;
+
         let vault = storage::get_vault(&env, vault_id)
             .ok_or(VaultError::VaultNotFound)?;
 
         vault.owner.require_auth();
 
-        storage::set_pol_link(&env, vault_id, &pol_contract);
+        if vault.status != VaultStatus::Active {
+            return Err(VaultError::InvalidStatus);
+        }
+
+        let current = storage::get_guardians(&env, vault_id);
+        if current.iter().all(|g| guardians.contains(&g)) {
+            storage::remove_pending_guardians(&env, vault_id);
+            storage::set_guardians(&env, vault_id, &guardians);
+
+            env.events()
+                .publish((symbol_short!("vault"), symbol_short!("guardian")), vault_id);
+        } else {
+            let now = env.ledger().timestamp();
+            let executable_at = now + storage::get_beneficiary_delay(&env);
+            storage::set_pending_guardians(
+                &env,
+                vault_id,
+                &PendingGuardians { guardians, queued_at: now, executable_at },
+            );
+
+            env.events()
+                .publish((symbol_short!("vault"), symbol_short!("gqueued")), (vault_id, executable_at));
+        }
 
         Ok(())
     }
 
-    /// Transition vault status. Only callable by the linked ProofOfLife contract or admin.
-    pub fn transition_status(
+    /// Apply a queued guardian change once its delay has elapsed.
+    pub fn execute_pending_guardians(env: Env, vault_id: VaultId) -> Result<(), VaultError> {
+        ensure_writable(&env, vault_id)?;
+
+        let vault = storage::get_vault(&env, vault_id)
+            .ok_or(VaultError::VaultNotFound)?;
+
+        vault.owner.require_auth();
+
+        if vault.status != VaultStatus::Active {
+            return Err(VaultError::InvalidStatus);
+        }
+
+        let pending = storage::get_pending_guardians(&env, vault_id)
+            .ok_or(VaultError::NoPendingChange)?;
+
+        if env.ledger().timestamp() < pending.executable_at {
+            return Err(VaultError::TimelockActive);
+        }
+
+        storage::remove_pending_guardians(&env, vault_id);
+        storage::set_guardians(&env, vault_id, &pending.guardians);
+
+        env.events()
+            .publish((symbol_short!("vault"), symbol_short!("guardian")), vault_id);
+
+        Ok(())
+    }
+
+    /// Cancel a queued guardian change. Callable by the owner or a current guardian.
+    pub fn cancel_pending_guardians(
         env: Env,
         vault_id: VaultId,
         caller: Address,
-        new_status: VaultStatus,
     ) -> Result<(), VaultError> {
         caller.require_auth();
 
-        let mut vault = storage::get_vault(&env, vault_id)
+        let vault = storage::get_vault(&env, vault_id)
             .ok_or(VaultError::VaultNotFound)?;
 
-        let admin = storage::get_admin(&env);
-        let pol_link = storage::get_pol_link(&env, vault_id);
-
-        let is_admin = caller == admin;
-        let is_pol = pol_link.map_or(false, |pol| caller == pol);
-
-        if !is_admin && !is_pol {
+        let is_guardian = storage::get_guardians(&env, vault_id).contains(&caller);
+        if caller != vault.owner && !is_guardian {
             return Err(VaultError::NotAuthorized);
         }
 
-        vault.status = new_status.clone();
-        vault.last_updated = env.ledger().timestamp();
-        storage::set_vault(&env, vault_id, &vault);
+        if storage::get_pending_guardians(&env, vault_id).is_none() {
+            return Err(VaultError::NoPendingChange);
+        }
+        storage::remove_pending_guardians(&env, vault_id);
 
         env.events()
-            .publish((symbol_short!("vault"), symbol_short!("status")), (vault_id, new_status));
+            .publish((symbol_short!("vault"), symbol_short!("gcancel")), (vault_id, caller));
+
+        Ok(())
+    }
+
+    /// Get the queued guardian change for a vault, if any.
+    pub fn get_pending_guardians(
+        env: Env,
+        vault_id: VaultId,
+    ) -> Result<Option<PendingGuardians>, VaultError> {
+        if storage::get_vault(&env, vault_id).is_none() {
+            return Err(VaultError::VaultNotFound);
+        }
+        Ok(storage::get_pending_guardians(&env, vault_id))
+    }
+
+    /// Get guardians for a vault.
+    pub fn get_guardians(env: Env, vault_id: VaultId) -> Result<Vec<Address>, VaultError> {
+        if storage::get_vault(&env, vault_id).is_none() {
+            return Err(VaultError::VaultNotFound);
+        }
+        Ok(storage::get_guardians(&env, vault_id))
+    }
 
+    /// Set the delay (seconds) applied to beneficiary changes. Admin only,
+    /// and never below `MIN_BENEFICIARY_DELAY`.
+    pub fn set_beneficiary_delay(env: Env, delay_secs: u64) -> Result<(), VaultError> {
+        let admin = storage::get_admin(&env);
+        admin.require_auth();
+        if delay_secs < storage::MIN_BENEFICIARY_DELAY {
+            return Err(VaultError::InvalidLimit);
+        }
+        storage::set_beneficiary_delay(&env, delay_secs);
         Ok(())
     }
+
+    /// Get the delay (seconds) applied to beneficiary changes.
+    pub fn get_beneficiary_delay(env: Env) -> u64 {
+        storage::get_beneficiary_delay(&env)
+    }
+
+    /// Get beneficiary commitments for a vault.
+    pub fn get_beneficiary_commitments(
+        env: Env,
+        vault_id: VaultId,
+    ) -> Result<Vec<BeneficiaryCommitment>, VaultError> {
+        if storage::get_vault(&env, vault_id).is_none() {
+            return Err(VaultError::VaultNotFound);
+        }
+        Ok(storage::get_commitments(&env, vault_id))
+    }
+
+    /// Compute the commitment for a beneficiary address and salt.
+    pub fn compute_commitment(env: Env, beneficiary: Address, salt: BytesN<32>) -> BytesN<32> {
+        commitment_of(&env, &beneficiary, &salt)
+    }
+
+    /// Claim a beneficiary's share of every token in a Triggered vault.
+    /// Returns the amounts paid.
+    pub fn claim(
+        env: Env,
+        vault_id: VaultId,
+        beneficiary: Address,
+    ) -> Result<Vec<TokenBalance>, VaultError> {
+        ensure_writable(&env, vault_id)?;
+
+        beneficiary.require_auth();
+
+        let mut vault = storage::get_vault(&env, vault_id)
+            .ok_or(VaultError::VaultNotFound)?;
+
+        if vault.status != VaultStatus::Triggered {
+            return Err(VaultError::InvalidStatus);
+        }
+        ensure_claims_open(&env, vault_id)?;
+        ensure_heirs_open(&env, vault_id)?;
+
+        let mut beneficiaries = storage::get_beneficiaries(&env, vault_id);
+        let index = beneficiaries
+            .iter()
+            .position(|b| b.address == beneficiary)
+            .ok_or(VaultError::NotABeneficiary)? as u32;
+
+        let mut entry = beneficiaries.get(index).unwrap();
+        if entry.claimed {
+            return Err(VaultError::AlreadyClaimed);
+        }
+        entry.claimed = true;
+        beneficiaries.set(index, entry.clone());
+        storage::set_beneficiaries(&env, vault_id, &beneficiaries);
+
+        let is_last = beneficiaries.iter().all(|b| b.claimed);
+        recall_all(&env, vault_id)?;
+        settle(&env, vault_id);
+        Ok(pay_share(&env, &mut vault, entry.percentage, is_last, &beneficiary))
+    }
+
+    /// Claim a hidden beneficiary's share by revealing the address and salt.
+    pub fn claim_committed(
+        env: Env,
+        vault_id: VaultId,
+        beneficiary: Address,
+        salt: BytesN<32>,
+    ) -> Result<Vec<TokenBalance>, VaultError> {
+        ensure_writable(&env, vault_id)?;
+
+        beneficiary.require_auth();
+
+        let mut vault = storage::get_vault(&env, vault_id)
+            .ok_or(VaultError::VaultNotFound)?;
+
+        if vault.status != VaultStatus::Triggered {
+            return Err(VaultError::InvalidStatus);
+        }
+        ensure_claims_open(&env, vault_id)?;
+        ensure_heirs_open(&env, vault_id)?;
+
+        let commitment = commitment_of(&env, &beneficiary, &salt);
+        let mut commitments = storage::get_commitments(&env, vault_id);
+        let index = commitments
+            .iter()
+            .position(|c| c.commitment == commitment)
+            .ok_or(VaultError::InvalidCommitment)? as u32;
+
+        let mut entry = commitments.get(index).unwrap();
+        if entry.claimed {
+            return Err(VaultError::AlreadyClaimed);
+        }
+        entry.claimed = true;
+        commitments.set(index, entry.clone());
+        storage::set_commitments(&env, vault_id, &commitments);
+
+        let is_last = commitments.iter().all(|c| c.claimed);
+        recall_all(&env, vault_id)?;
+        settle(&env, vault_id);
+        Ok(pay_share(&env, &mut vault, entry.percentage, is_last, &beneficiary))
+    }
+
+    /// Add a withdrawal destination. It becomes usable after
+    /// `WITHDRAWAL_ADDRESS_DELAY`, so a compromised key cannot add and drain
+    /// to a new address in one go.
+    pub fn add_withdrawal_address(
+        env: Env,
+        vault_id: VaultId,
+        address: Address,
+    ) -> Result<u64, VaultError> {
+        ensure_writable(&env, vault_id)?;
+
+        let vault = storage::get_vault(&env, vault_id)
+            .ok_or(VaultError::VaultNotFound)?;
+
+        vault.owner.require_auth();
+
+        if vault.status != VaultStatus::Active {
+            return Err(VaultError::InvalidStatus);
+        }
+
+        let mut addresses = live_withdrawal_addresses(&env, vault_id);
+        if let Some(index) = addresses.iter().position(|a| a.address == address) {
+            // Re-adding an address cancels its pending removal.
+            let mut existing = addresses.get(index as u32).unwrap();
+            existing.removed_at = 0;
+            addresses.set(index as u32, existing.clone());
+            storage::set_withdrawal_addresses(&env, vault_id, &addresses);
+            return Ok(existing.active_from);
+        }
+        if addresses.len() >= MAX_WITHDRAWAL_ADDRESSES {
+            return Err(VaultError::TooManyAddresses);
+        }
+
+        let active_from = env.ledger().timestamp() + WITHDRAWAL_ADDRESS_DELAY;
+        addresses.push_back(WithdrawalAddress { address: address.clone(), active_from, removed_at: 0 });
+        storage::set_withdrawal_addresses(&env, vault_id, &addresses);
+
+        env.events().publish(
+            (symbol_short!("vault"), symbol_short!("wd_add")),
+            (vault_id, address, active_from),
+        );
+
+        Ok(active_from)
+    }
+
+    /// Schedule the removal of a withdrawal destination. The entry stays in
+    /// force for `WITHDRAWAL_ADDRESS_DELAY`, so emptying the allowlist (which
+    /// lifts it) is as slow as adding a new destination. Returns the time the
+    /// removal takes effect.
+    pub fn remove_withdrawal_address(
+        env: Env,
+        vault_id: VaultId,
+        address: Address,
+    ) -> Result<u64, VaultError> {
+        ensure_writable(&env, vault_id)?;
+
+        let vault = storage::get_vault(&env, vault_id)
+            .ok_or(VaultError::VaultNotFound)?;
+
+        vault.owner.require_auth();
+
+        let mut addresses = live_withdrawal_addresses(&env, vault_id);
+        let index = addresses
+            .iter()
+            .position(|a| a.address == address)
+            .ok_or(VaultError::DestinationNotAllowed)? as u32;
+        let mut entry = addresses.get(index).unwrap();
+        if entry.removed_at == 0 {
+            entry.removed_at = env.ledger().timestamp() + WITHDRAWAL_ADDRESS_DELAY;
+            addresses.set(index, entry.clone());
+        }
+        storage::set_withdrawal_addresses(&env, vault_id, &addresses);
+
+        env.events().publish(
+            (symbol_short!("vault"), symbol_short!("wd_rem")),
+            (vault_id, address, entry.removed_at),
+        );
+
+        Ok(entry.removed_at)
+    }
+
+    /// Get the withdrawal allowlist, including entries not yet active and
+    /// entries whose removal is still pending.
+    pub fn get_withdrawal_addresses(
+        env: Env,
+        vault_id: VaultId,
+    ) -> Result<Vec<WithdrawalAddress>, VaultError> {
+        if storage::get_vault(&env, vault_id).is_none() {
+            return Err(VaultError::VaultNotFound);
+        }
+        Ok(live_withdrawal_addresses(&env, vault_id))
+    }
+
+    /// Set or clear the rolling 24h withdrawal limit for a token. Without a
+    /// limit (or with an `alert_limit` of 0) withdrawals stay blocked in Alert.
+    /// Tightening applies at once; raising or removing a cap only takes effect
+    /// after the allowlist delay, so it cannot be lifted to drain the vault.
+    pub fn set_withdrawal_limit(
+        env: Env,
+        vault_id: VaultId,
+        token: Address,
+        limit: Option<WithdrawalLimit>,
+    ) -> Result<(), VaultError> {
+        ensure_writable(&env, vault_id)?;
+
+        let vault = storage::get_vault(&env, vault_id)
+            .ok_or(VaultError::VaultNotFound)?;
+
+        vault.owner.require_auth();
+
+        if vault.status != VaultStatus::Active {
+            return Err(VaultError::InvalidStatus);
+        }
+
+        if let Some(l) = &limit {
+            if l.daily_limit <= 0 || l.alert_limit < 0 || l.alert_limit > l.daily_limit {
+                return Err(VaultError::InvalidLimit);
+            }
+        }
+
+        let current = current_withdrawal_limit(&env, vault_id, &token);
+        match &current {
+            Some(c) => storage::set_withdrawal_limit(&env, vault_id, &token, c),
+            None => storage::remove_withdrawal_limit(&env, vault_id, &token),
+        }
+
+        let tighter = match (&limit, &current) {
+            (Some(l), Some(c)) => l.daily_limit <= c.daily_limit && l.alert_limit <= c.alert_limit,
+            // A first cap must not open up withdrawals in Alert.
+            (Some(l), None) => l.alert_limit == 0,
+            (None, None) => true,
+            (None, Some(_)) => false,
+        };
+
+        if tighter {
+            storage::remove_pending_withdrawal_limit(&env, vault_id, &token);
+            if let Some(l) = &limit {
+                storage::set_withdrawal_limit(&env, vault_id, &token, l);
+            }
+
+            env.events()
+                .publish((symbol_short!("vault"), symbol_short!("wd_limit")), (vault_id, token, limit));
+        } else {
+            let executable_at = env.ledger().timestamp() + WITHDRAWAL_ADDRESS_DELAY;
+            storage::set_pending_withdrawal_limit(
+                &env,
+                vault_id,
+                &token,
+                &PendingWithdrawalLimit {
+                    daily_limit: limit.as_ref().map_or(0, |l| l.daily_limit),
+                    alert_limit: limit.as_ref().map_or(0, |l| l.alert_limit),
+                    executable_at,
+                },
+            );
+
+            env.events().publish(
+                (symbol_short!("vault"), symbol_short!("wdl_queue")),
+                (vault_id, token, limit, executable_at),
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Get the withdrawal limit for a token, if one is set.
+    pub fn get_withdrawal_limit(
+        env: Env,
+        vault_id: VaultId,
+        token: Address,
+    ) -> Result<Option<WithdrawalLimit>, VaultError> {
+        if storage::get_vault(&env, vault_id).is_none() {
+            return Err(VaultError::VaultNotFound);
+        }
+        Ok(current_withdrawal_limit(&env, vault_id, &token))
+    }
+
+    /// Get a raised or removed withdrawal limit still waiting out its delay.
+    pub fn get_pending_withdrawal_limit(
+        env: Env,
+        vault_id: VaultId,
+        token: Address,
+    ) -> Result<Option<PendingWithdrawalLimit>, VaultError> {
+        if storage::get_vault(&env, vault_id).is_none() {
+            return Err(VaultError::VaultNotFound);
+        }
+        Ok(storage::get_pending_withdrawal_limit(&env, vault_id, &token))
+    }
+
+    /// Pay `recipient` a fixed `amount` of `token` every `period_secs` while
+    /// the vault is Active, until `end_at`. The first payment is due at once.
+    /// Replaces any existing allowance for the same recipient and token.
+    pub fn set_allowance(
+        env: Env,
+        vault_id: VaultId,
+        recipient: Address,
+        token: Address,
+        amount: i128,
+        period_secs: u64,
+        end_at: u64,
+    ) -> Result<(), VaultError> {
+        ensure_writable(&env, vault_id)?;
+
+        let vault = storage::get_vault(&env, vault_id)
+            .ok_or(VaultError::VaultNotFound)?;
+
+        vault.owner.require_auth();
+
+        if vault.status != VaultStatus::Active {
+            return Err(VaultError::InvalidStatus);
+        }
+        if amount <= 0 {
+            return Err(VaultError::ZeroAmount);
+        }
+        let now = env.ledger().timestamp();
+        if period_secs == 0 || end_at <= now {
+            return Err(VaultError::InvalidSchedule);
+        }
+        ensure_destination_allowed(&env, &vault, &recipient)?;
+
+        let mut allowances = storage::get_allowances(&env, vault_id);
+        let allowance = Allowance {
+            recipient: recipient.clone(),
+            token: token.clone(),
+            amount,
+            period_secs,
+            next_due: now,
+            end_at,
+        };
+        match allowances.iter().position(|a| a.recipient == recipient && a.token == token) {
+            Some(index) => allowances.set(index as u32, allowance),
+            None => {
+                if allowances.len() >= MAX_ALLOWANCES {
+                    return Err(VaultError::TooManyAllowances);
+                }
+                allowances.push_back(allowance);
+            }
+        }
+        storage::set_allowances(&env, vault_id, &allowances);
+
+        env.events().publish(
+            (symbol_short!("vault"), symbol_short!("allow")),
+            (vault_id, recipient, token, amount, period_secs),
+        );
+
+        Ok(())
+    }
+
+    /// Cancel an allowance.
+    pub fn remove_allowance(
+        env: Env,
+        vault_id: VaultId,
+        recipient: Address,
+        token: Address,
+    ) -> Result<(), VaultError> {
+        ensure_writable(&env, vault_id)?;
+
+        let vault = storage::get_vault(&env, vault_id)
+            .ok_or(VaultError::VaultNotFound)?;
+
+        vault.owner.require_auth();
+
+        let mut allowances = storage::get_allowances(&env, vault_id);
+        let index = allowances
+            .iter()
+            .position(|a| a.recipient == recipient && a.token == token)
+            .ok_or(VaultError::AllowanceNotFound)? as u32;
+        allowances.remove(index);
+        storage::set_allowances(&env, vault_id, &allowances);
+
+        env.events()
+            .publish((symbol_short!("vault"), symbol_short!("allow_rm")), (vault_id, recipient, token));
+
+        Ok(())
+    }
+
+    /// Pay the next due period of an allowance. Callable by anyone; only
+    /// while the vault is Active. Payments count towards the withdrawal limit
+    /// and reduce the balance left for inheritance.
+    pub fn execute_allowance(
+        env: Env,
+        vault_id: VaultId,
+        recipient: Address,
+        token: Address,
+    ) -> Result<i128, VaultError> {
+        ensure_writable(&env, vault_id)?;
+
+        let mut vault = storage::get_vault(&env, vault_id)
+            .ok_or(VaultError::VaultNotFound)?;
+
+        if vault.status != VaultStatus::Active {
+            return Err(VaultError::InvalidStatus);
+        }
+
+        let mut allowances = storage::get_allowances(&env, vault_id);
+        let index = allowances
+            .iter()
+            .position(|a| a.recipient == recipient && a.token == token)
+            .ok_or(VaultError::AllowanceNotFound)? as u32;
+        let mut allowance = allowances.get(index).unwrap();
+
+        let now = env.ledger().timestamp();
+        if allowance.next_due > now || allowance.next_due >= allowance.end_at {
+            return Err(VaultError::NotDue);
+        }
+
+        ensure_destination_allowed(&env, &vault, &recipient)?;
+        let idle = storage::get_balance(&env, vault_id, &token) - storage::get_invested(&env, vault_id, &token);
+        if idle < allowance.amount {
+            recall(&env, vault_id, &token)?;
+        }
+        if storage::get_balance(&env, vault_id, &token) < allowance.amount {
+            return Err(VaultError::InsufficientBalance);
+        }
+        if let Some(limit) = current_withdrawal_limit(&env, vault_id, &token) {
+            record_withdrawal(&env, vault_id, &token, allowance.amount, limit.daily_limit)?;
+        }
+
+        token::Client::new(&env, &token)
+            .transfer(&env.current_contract_address(), &recipient, &allowance.amount);
+        storage::decrease_balance(&env, vault_id, &token, allowance.amount);
+
+        allowance.next_due += allowance.period_secs;
+        if allowance.next_due >= allowance.end_at {
+            allowances.remove(index);
+        } else {
+            allowances.set(index, allowance.clone());
+        }
+        storage::set_allowances(&env, vault_id, &allowances);
+
+        vault.last_updated = now;
+        storage::set_vault(&env, vault_id, &vault);
+
+        env.events().publish(
+            (symbol_short!("vault"), symbol_short!("allow_pay")),
+            (vault_id, recipient, token, allowance.amount),
+        );
+
+        Ok(allowance.amount)
+    }
+
+    /// Get the allowances on a vault.
+    pub fn get_allowances(env: Env, vault_id: VaultId) -> Result<Vec<Allowance>, VaultError> {
+        if storage::get_vault(&env, vault_id).is_none() {
+            return Err(VaultError::VaultNotFound);
+        }
+        Ok(storage::get_allowances(&env, vault_id))
+    }
+
+    /// Approve a yield strategy for the vault's idle funds, or clear it with
+    /// `None`. The strategy can only be replaced once nothing is invested.
+    pub fn set_strategy(env: Env, vault_id: VaultId, strategy: Option<Address>) -> Result<(), VaultError> {
+        ensure_writable(&env, vault_id)?;
+
+        let vault = storage::get_vault(&env, vault_id)
+            .ok_or(VaultError::VaultNotFound)?;
+
+        vault.owner.require_auth();
+
+        if vault.status != VaultStatus::Active {
+            return Err(VaultError::InvalidStatus);
+        }
+
+        for token in storage::get_vault_tokens(&env, vault_id).iter() {
+            if storage::get_invested(&env, vault_id, &token) > 0 {
+                return Err(VaultError::StrategyInUse);
+            }
+        }
+
+        match &strategy {
+            Some(s) => storage::set_strategy(&env, vault_id, s),
+            None => storage::remove_strategy(&env, vault_id),
+        }
+
+        env.events()
+            .publish((symbol_short!("vault"), symbol_short!("strategy")), (vault_id, strategy));
+
+        Ok(())
+    }
+
+    /// Get the vault's approved strategy, if any.
+    pub fn get_strategy(env: Env, vault_id: VaultId) -> Result<Option<Address>, VaultError> {
+        if storage::get_vault(&env, vault_id).is_none() {
+            return Err(VaultError::VaultNotFound);
+        }
+        Ok(storage::get_strategy(&env, vault_id))
+    }
+
+    /// Move idle funds into the vault's strategy. Invested funds still count
+    /// toward the vault balance.
+    pub fn invest(env: Env, vault_id: VaultId, token: Address, amount: i128) -> Result<(), VaultError> {
+        ensure_writable(&env, vault_id)?;
+
+        if amount <= 0 {
+            return Err(VaultError::ZeroAmount);
+        }
+
+        let vault = storage::get_vault(&env, vault_id)
+            .ok_or(VaultError::VaultNotFound)?;
+
+        vault.owner.require_auth();
+
+        if vault.status != VaultStatus::Active {
+            return Err(VaultError::InvalidStatus);
+        }
+
+        let strategy = storage::get_strategy(&env, vault_id)
+            .ok_or(VaultError::StrategyNotSet)?;
+
+        let invested = storage::get_invested(&env, vault_id, &token);
+        if storage::get_balance(&env, vault_id, &token) - invested < amount {
+            return Err(VaultError::InsufficientBalance);
+        }
+
+        let this = env.current_contract_address();
+        token::Client::new(&env, &token).transfer(&this, &strategy, &amount);
+        match StrategyClient::new(&env, &strategy).try_deposit(&this, &vault_id, &token, &amount) {
+            Ok(Ok(())) => {}
+            _ => return Err(VaultError::StrategyFailed),
+        }
+        storage::set_invested(&env, vault_id, &token, invested + amount);
+
+        env.events()
+            .publish((symbol_short!("vault"), symbol_short!("invest")), (vault_id, token, amount));
+
+        Ok(())
+    }
+
+    /// Pull a token's whole position back from the strategy, booking any
+    /// gain or loss against the vault balance. Owner-only while the vault
+    /// lives; anyone may recall once it has triggered so claims can proceed.
+    pub fn divest(env: Env, vault_id: VaultId, token: Address) -> Result<i128, VaultError> {
+        ensure_writable(&env, vault_id)?;
+
+        let vault = storage::get_vault(&env, vault_id)
+            .ok_or(VaultError::VaultNotFound)?;
+
+        match vault.status {
+            VaultStatus::Triggered | VaultStatus::Distributed => {}
+            VaultStatus::Closed => return Err(VaultError::InvalidStatus),
+            _ => vault.owner.require_auth(),
+        }
+
+        recall(&env, vault_id, &token)
+    }
+
+    /// Principal of a token currently held by the vault's strategy.
+    pub fn get_invested(env: Env, vault_id: VaultId, token: Address) -> Result<i128, VaultError> {
+        if storage::get_vault(&env, vault_id).is_none() {
+            return Err(VaultError::VaultNotFound);
+        }
+        Ok(storage::get_invested(&env, vault_id, &token))
+    }
+
+    /// Set specific-asset bequests, paid in list order before the percentage
+    /// residue. Fixed amounts per token may not exceed the current balance, and
+    /// a `WholeToken` bequest must be the only bequest of its token. Once the
+    /// vault has any payout configuration the change is queued behind the
+    /// beneficiary delay.
+    pub fn set_bequests(
+        env: Env,
+        vault_id: VaultId,
+        bequests: Vec<Bequest>,
+    ) -> Result<(), VaultError> {
+        ensure_writable(&env, vault_id)?;
+
+        let vault = storage::get_vault(&env, vault_id)
+            .ok_or(VaultError::VaultNotFound)?;
+
+        vault.owner.require_auth();
+
+        if vault.status != VaultStatus::Active {
+            return Err(VaultError::InvalidStatus);
+        }
+
+        if bequests.len() > MAX_BEQUESTS {
+            return Err(VaultError::InvalidBequests);
+        }
+
+        let mut fixed_totals: Map<Address, i128> = Map::new(&env);
+        let mut whole_tokens: Vec<Address> = Vec::new(&env);
+        for b in bequests.iter() {
+            if b.paid {
+                return Err(VaultError::InvalidBequests);
+            }
+            match b.kind {
+                BequestKind::Fixed => {
+                    if b.amount <= 0 {
+                        return Err(VaultError::ZeroAmount);
+                    }
+                    let total = fixed_totals.get(b.token.clone()).unwrap_or(0) + b.amount;
+                    fixed_totals.set(b.token.clone(), total);
+                }
+                BequestKind::WholeToken => {
+                    if b.amount != 0 || whole_tokens.contains(&b.token) {
+                        return Err(VaultError::InvalidBequests);
+                    }
+                    whole_tokens.push_back(b.token.clone());
+                }
+            }
+        }
+
+        for token in whole_tokens.iter() {
+            if fixed_totals.contains_key(token) {
+                return Err(VaultError::OverAllocated);
+            }
+        }
+        for (token, total) in fixed_totals.iter() {
+            if total > storage::get_balance(&env, vault_id, &token) {
+                return Err(VaultError::OverAllocated);
+            }
+        }
+
+        queue_or_apply_waterfall(&env, vault_id, WaterfallChange::Bequests(bequests));
+
+        Ok(())
+    }
+
+    /// Get bequests for a vault.
+    pub fn get_bequests(env: Env, vault_id: VaultId) -> Result<Vec<Bequest>, VaultError> {
+        if storage::get_vault(&env, vault_id).is_none() {
+            return Err(VaultError::VaultNotFound);
+        }
+        Ok(storage::get_bequests(&env, vault_id))
+    }
+
+    /// Claim every unpaid bequest left to `recipient` in a Triggered vault.
+    pub fn claim_bequests(
+        env: Env,
+        vault_id: VaultId,
+        recipient: Address,
+    ) -> Result<Vec<TokenBalance>, VaultError> {
+        ensure_writable(&env, vault_id)?;
+
+        recipient.require_auth();
+
+        let mut vault = storage::get_vault(&env, vault_id)
+            .ok_or(VaultError::VaultNotFound)?;
+
+        if vault.status != VaultStatus::Triggered {
+            return Err(VaultError::InvalidStatus);
+        }
+        ensure_claims_open(&env, vault_id)?;
+        ensure_heirs_open(&env, vault_id)?;
+
+        recall_all(&env, vault_id)?;
+        settle(&env, vault_id);
+
+        let mut bequests = storage::get_bequests(&env, vault_id);
+        let mut paid = Vec::new(&env);
+        for i in 0..bequests.len() {
+            let mut b = bequests.get(i).unwrap();
+            if b.recipient != recipient || b.paid {
+                continue;
+            }
+            let received = pay_out(&env, vault_id, &b.token, &recipient, b.amount);
+            b.paid = true;
+            bequests.set(i, b.clone());
+            paid.push_back(TokenBalance { token: b.token, amount: received });
+        }
+
+        if paid.is_empty() {
+            return Err(VaultError::NothingToClaim);
+        }
+        storage::set_bequests(&env, vault_id, &bequests);
+
+        env.events()
+            .publish((symbol_short!("vault"), symbol_short!("bequest")), (vault_id, &recipient));
+
+        mark_if_distributed(&env, &mut vault);
+
+        Ok(paid)
+    }
+
+    /// Name an executor, paid `fee_bps` of every token before creditors and
+    /// heirs. The fee is capped at `MAX_EXECUTOR_FEE_BPS`. Queued like
+    /// `set_bequests`.
+    pub fn set_executor(
+        env: Env,
+        vault_id: VaultId,
+        executor: Address,
+        fee_bps: u32,
+    ) -> Result<(), VaultError> {
+        ensure_writable(&env, vault_id)?;
+
+        let vault = storage::get_vault(&env, vault_id)
+            .ok_or(VaultError::VaultNotFound)?;
+
+        vault.owner.require_auth();
+
+        if vault.status != VaultStatus::Active {
+            return Err(VaultError::InvalidStatus);
+        }
+
+        if fee_bps > MAX_EXECUTOR_FEE_BPS {
+            return Err(VaultError::FeeTooHigh);
+        }
+
+        let fee = ExecutorFee { executor, fee_bps, paid: false };
+        queue_or_apply_waterfall(&env, vault_id, WaterfallChange::Executor(fee));
+
+        Ok(())
+    }
+
+    /// Get the executor fee for a vault, if one is set.
+    pub fn get_executor(env: Env, vault_id: VaultId) -> Result<Option<ExecutorFee>, VaultError> {
+        if storage::get_vault(&env, vault_id).is_none() {
+            return Err(VaultError::VaultNotFound);
+        }
+        Ok(storage::get_executor(&env, vault_id))
+    }
+
+    /// Set the creditor claims, paid in list order after the executor fee and
+    /// before any bequest or percentage share. Queued like `set_bequests`.
+    pub fn set_creditors(
+        env: Env,
+        vault_id: VaultId,
+        creditors: Vec<CreditorClaim>,
+    ) -> Result<(), VaultError> {
+        ensure_writable(&env, vault_id)?;
+
+        let vault = storage::get_vault(&env, vault_id)
+            .ok_or(VaultError::VaultNotFound)?;
+
+        vault.owner.require_auth();
+
+        if vault.status != VaultStatus::Active {
+            return Err(VaultError::InvalidStatus);
+        }
+
+        if creditors.len() > MAX_CREDITORS {
+            return Err(VaultError::InvalidCreditors);
+        }
+
+        for c in creditors.iter() {
+            if c.amount <= 0 {
+                return Err(VaultError::ZeroAmount);
+            }
+            if c.paid {
+                return Err(VaultError::InvalidCreditors);
+            }
+        }
+
+        queue_or_apply_waterfall(&env, vault_id, WaterfallChange::Creditors(creditors));
+
+        Ok(())
+    }
+
+    /// Get creditor claims for a vault.
+    pub fn get_creditors(env: Env, vault_id: VaultId) -> Result<Vec<CreditorClaim>, VaultError> {
+        if storage::get_vault(&env, vault_id).is_none() {
+            return Err(VaultError::VaultNotFound);
+        }
+        Ok(storage::get_creditors(&env, vault_id))
+    }
+
+    /// Pay the executor fee. First tier of the waterfall. Anyone may push the
+    /// payment, since it can only go to the recorded executor; it lapses
+    /// `PRIORITY_CLAIM_WINDOW` after the trigger.
+    pub fn claim_executor_fee(env: Env, vault_id: VaultId) -> Result<Vec<TokenBalance>, VaultError> {
+        ensure_writable(&env, vault_id)?;
+
+        let mut vault = storage::get_vault(&env, vault_id)
+            .ok_or(VaultError::VaultNotFound)?;
+
+        if vault.status != VaultStatus::Triggered {
+            return Err(VaultError::InvalidStatus);
+        }
+        ensure_claims_open(&env, vault_id)?;
+
+        let mut fee = storage::get_executor(&env, vault_id)
+            .ok_or(VaultError::NotAuthorized)?;
+
+        if fee.paid {
+            return Err(VaultError::AlreadyClaimed);
+        }
+        if priority_lapsed(&env, vault_id) {
+            return Err(VaultError::PriorityClaimLapsed);
+        }
+        recall_all(&env, vault_id)?;
+
+        let mut paid = Vec::new(&env);
+        for token in storage::get_vault_tokens(&env, vault_id).iter() {
+            let amount = storage::get_balance(&env, vault_id, &token) * fee.fee_bps as i128 / 10_000;
+            let received = pay_out(&env, vault_id, &token, &fee.executor, amount);
+            paid.push_back(TokenBalance { token, amount: received });
+        }
+
+        fee.paid = true;
+        storage::set_executor(&env, vault_id, &fee);
+
+        env.events()
+            .publish((symbol_short!("vault"), symbol_short!("exec_paid")), (vault_id, &fee.executor));
+
+        mark_if_distributed(&env, &mut vault);
+
+        Ok(paid)
+    }
+
+    /// Waive the executor fee so creditors can claim.
+    pub fn release_executor_fee(env: Env, vault_id: VaultId) -> Result<(), VaultError> {
+        ensure_writable(&env, vault_id)?;
+
+        if storage::get_vault(&env, vault_id).is_none() {
+            return Err(VaultError::VaultNotFound);
+        }
+
+        let mut fee = storage::get_executor(&env, vault_id)
+            .ok_or(VaultError::NotAuthorized)?;
+        fee.executor.require_auth();
+
+        if fee.paid {
+            return Err(VaultError::AlreadyClaimed);
+        }
+
+        fee.paid = true;
+        fee.fee_bps = 0;
+        storage::set_executor(&env, vault_id, &fee);
+
+        env.events()
+            .publish((symbol_short!("vault"), symbol_short!("exec_rel")), (vault_id, &fee.executor));
+
+        Ok(())
+    }
+
+    /// Pay every outstanding debt owed to `creditor`. Available once the
+    /// executor fee has been paid or released. Anyone may push the payment,
+    /// since it can only go to the creditor; it lapses
+    /// `PRIORITY_CLAIM_WINDOW` after the trigger.
+    pub fn claim_debt(
+        env: Env,
+        vault_id: VaultId,
+        creditor: Address,
+    ) -> Result<Vec<TokenBalance>, VaultError> {
+        ensure_writable(&env, vault_id)?;
+
+        let mut vault = storage::get_vault(&env, vault_id)
+            .ok_or(VaultError::VaultNotFound)?;
+
+        if vault.status != VaultStatus::Triggered {
+            return Err(VaultError::InvalidStatus);
+        }
+        ensure_claims_open(&env, vault_id)?;
+        if priority_lapsed(&env, vault_id) {
+            return Err(VaultError::PriorityClaimLapsed);
+        }
+        if storage::get_executor(&env, vault_id).is_some_and(|e| !e.paid) {
+            return Err(VaultError::PriorTierPending);
+        }
+
+        recall_all(&env, vault_id)?;
+
+        let mut creditors = storage::get_creditors(&env, vault_id);
+        let mut paid = Vec::new(&env);
+        for i in 0..creditors.len() {
+            let mut c = creditors.get(i).unwrap();
+            if c.creditor != creditor || c.paid {
+                continue;
+            }
+            let amount = creditor_allocation(&env, vault_id, &creditors, i);
+            let received = pay_out(&env, vault_id, &c.token, &creditor, amount);
+            c.amount = amount;
+            c.paid = true;
+            creditors.set(i, c.clone());
+            paid.push_back(TokenBalance { token: c.token, amount: received });
+        }
+
+        if paid.is_empty() {
+            return Err(VaultError::NothingToClaim);
+        }
+        storage::set_creditors(&env, vault_id, &creditors);
+
+        env.events()
+            .publish((symbol_short!("vault"), symbol_short!("debt_paid")), (vault_id, &creditor));
+
+        mark_if_distributed(&env, &mut vault);
+
+        Ok(paid)
+    }
+
+    /// Waive every outstanding debt owed to `creditor`, releasing the funds
+    /// to the next tier.
+    pub fn release_debt(env: Env, vault_id: VaultId, creditor: Address) -> Result<(), VaultError> {
+        ensure_writable(&env, vault_id)?;
+
+        creditor.require_auth();
+
+        if storage::get_vault(&env, vault_id).is_none() {
+            return Err(VaultError::VaultNotFound);
+        }
+
+        let mut creditors = storage::get_creditors(&env, vault_id);
+        let mut released = false;
+        for i in 0..creditors.len() {
+            let mut c = creditors.get(i).unwrap();
+            if c.creditor == creditor && !c.paid {
+                c.amount = 0;
+                c.paid = true;
+                creditors.set(i, c);
+                released = true;
+            }
+        }
+
+        if !released {
+            return Err(VaultError::NothingToClaim);
+        }
+        storage::set_creditors(&env, vault_id, &creditors);
+
+        env.events()
+            .publish((symbol_short!("vault"), symbol_short!("debt_rel")), (vault_id, &creditor));
+
+        Ok(())
+    }
+
+    /// What each address would receive if every outstanding tier were paid
+    /// out of the current balances: executor, creditors, bequests, then the
+    /// percentage residue, net of the vault's claim fee. Committed
+    /// beneficiaries are omitted since their addresses are not known on-chain.
+    pub fn preview_distribution(env: Env, vault_id: VaultId) -> Result<Vec<Payout>, VaultError> {
+        if storage::get_vault(&env, vault_id).is_none() {
+            return Err(VaultError::VaultNotFound);
+        }
+
+        let tokens = storage::get_vault_tokens(&env, vault_id);
+        let mut remaining: Map<Address, i128> = Map::new(&env);
+        for token in tokens.iter() {
+            let balance = storage::get_balance(&env, vault_id, &token);
+            remaining.set(token, balance);
+        }
+
+        let claim_bps = storage::get_vault_fees(&env, vault_id).claim_bps as i128;
+        let lapsed = priority_lapsed(&env, vault_id);
+        let mut payouts = Vec::new(&env);
+        let mut add = |recipient: &Address, token: &Address, amount: i128, remaining: &mut Map<Address, i128>| {
+            let available = remaining.get(token.clone()).unwrap_or(0);
+            let amount = amount.min(available);
+            remaining.set(token.clone(), available - amount);
+            if amount > 0 {
+                let received = amount - amount * claim_bps / 10_000;
+                payouts.push_back(Payout { recipient: recipient.clone(), token: token.clone(), amount: received });
+            }
+        };
+
+        if let Some(fee) = storage::get_executor(&env, vault_id).filter(|e| !e.paid && !lapsed) {
+            for token in tokens.iter() {
+                let amount = remaining.get(token.clone()).unwrap_or(0) * fee.fee_bps as i128 / 10_000;
+                add(&fee.executor, &token, amount, &mut remaining);
+            }
+        }
+
+        for c in storage::get_creditors(&env, vault_id).iter() {
+            if !c.paid && !lapsed {
+                add(&c.creditor, &c.token, c.amount, &mut remaining);
+            }
+        }
+
+        let settled = storage::is_settled(&env, vault_id);
+        let mut bequests = storage::get_bequests(&env, vault_id);
+        if !settled {
+            let mut pool = remaining.clone();
+            allocate_bequests(&mut bequests, &mut pool);
+        }
+        for b in bequests.iter() {
+            if !b.paid {
+                add(&b.recipient, &b.token, b.amount, &mut remaining);
+            }
+        }
+
+        let unclaimed: Vec<Beneficiary> = Vec::from_iter(
+            &env,
+            storage::get_beneficiaries(&env, vault_id).iter().filter(|b| !b.claimed),
+        );
+        for token in tokens.iter() {
+            let base = if settled {
+                storage::get_distribution_base(&env, vault_id, &token).unwrap_or(0)
+            } else {
+                remaining.get(token.clone()).unwrap_or(0)
+            };
+            for (i, b) in unclaimed.iter().enumerate() {
+                let amount = if i as u32 + 1 == unclaimed.len() {
+                    i128::MAX
+                } else {
+                    base * b.percentage as i128 / 10_000
+                };
+                add(&b.address, &token, amount, &mut remaining);
+            }
+        }
+
+        Ok(payouts)
+    }
+
+    /// Get vault info.
+    pub fn get_vault(env: Env, vault_id: VaultId) -> Result<VaultInfo, VaultError> {
+        storage::get_vault(&env, vault_id).ok_or(VaultError::VaultNotFound)
+    }
+
+    /// IDs of every vault `owner` owns, jointly or alone, in the order they
+    /// were acquired.
+    pub fn get_vaults_by_owner(env: Env, owner: Address) -> Vec<VaultId> {
+        storage::get_owner_vaults(&env, &owner)
+    }
+
+    /// Scan vault IDs `start..start + limit` and return the vaults found,
+    /// keeping only those in `status_filter` when one is given. A page may
+    /// hold fewer than `limit` vaults; callers advance `start` by `limit`
+    /// until it reaches `get_vault_count`. `limit` is capped at
+    /// `MAX_PAGE_SIZE`.
+    pub fn list_vaults(
+        env: Env,
+        start: VaultId,
+        limit: u32,
+        status_filter: Option<VaultStatus>,
+    ) -> Vec<VaultInfo> {
+        let end = storage::get_vault_count(&env)
+            .min(start.saturating_add(limit.min(MAX_PAGE_SIZE) as u64));
+
+        let mut vaults = Vec::new(&env);
+        for vault_id in start..end {
+            if let Some(vault) = storage::get_vault(&env, vault_id) {
+                if status_filter.as_ref().is_none_or(|s| *s == vault.status) {
+                    vaults.push_back(vault);
+                }
+            }
+        }
+        vaults
+    }
+
+    /// Number of vaults ever created; vault IDs run from 0 to this count.
+    pub fn get_vault_count(env: Env) -> u64 {
+        storage::get_vault_count(&env)
+    }
+
+    /// Get vault status.
+    pub fn get_status(env: Env, vault_id: VaultId) -> Result<VaultStatus, VaultError> {
+        let vault = storage::get_vault(&env, vault_id)
+            .ok_or(VaultError::VaultNotFound)?;
+        Ok(vault.status)
+    }
+
+    /// Get the timestamp at which the vault was last Triggered, if ever.
+    pub fn get_triggered_at(env: Env, vault_id: VaultId) -> Result<Option<u64>, VaultError> {
+        if storage::get_vault(&env, vault_id).is_none() {
+            return Err(VaultError::VaultNotFound);
+        }
+        Ok(storage::get_triggered_at(&env, vault_id))
+    }
+
+    /// Get a vault's balance of one token.
+    pub fn get_balance(env: Env, vault_id: VaultId, token: Address) -> Result<i128, VaultError> {
+        if storage::get_vault(&env, vault_id).is_none() {
+            return Err(VaultError::VaultNotFound);
+        }
+        Ok(storage::get_balance(&env, vault_id, &token))
+    }
+
+    /// Get every token balance held by a vault.
+    pub fn get_balances(env: Env, vault_id: VaultId) -> Result<Vec<TokenBalance>, VaultError> {
+        if storage::get_vault(&env, vault_id).is_none() {
+            return Err(VaultError::VaultNotFound);
+        }
+        let mut balances = Vec::new(&env);
+        for token in storage::get_vault_tokens(&env, vault_id).iter() {
+            let amount = storage::get_balance(&env, vault_id, &token);
+            balances.push_back(TokenBalance { token, amount });
+        }
+        Ok(balances)
+    }
+
+    /// Get beneficiaries for a vault.
+    pub fn get_beneficiaries(env: Env, vault_id: VaultId) -> Result<Vec<Beneficiary>, VaultError> {
+        if storage::get_vault(&env, vault_id).is_none() {
+            return Err(VaultError::VaultNotFound);
+        }
+        Ok(storage::get_beneficiaries(&env, vault_id))
+    }
+
+    /// Retire an Active vault. Every remaining balance goes back to the owner,
+    /// after refunding other contributors when the deposit config asks for it.
+    /// Beneficiary, payout and proof-of-life entries are deleted and the vault
+    /// is left in the terminal `Closed` status.
+    pub fn close_vault(env: Env, vault_id: VaultId) -> Result<Vec<TokenBalance>, VaultError> {
+        ensure_writable(&env, vault_id)?;
+
+        let mut vault = storage::get_vault(&env, vault_id)
+            .ok_or(VaultError::VaultNotFound)?;
+
+        vault.owner.require_auth();
+
+        if vault.status != VaultStatus::Active {
+            return Err(VaultError::InvalidStatus);
+        }
+
+        for token in storage::get_vault_tokens(&env, vault_id).iter() {
+            recall(&env, vault_id, &token)?;
+        }
+
+        let config = storage::get_deposit_config(&env, vault_id);
+        let contributors = storage::get_contributors(&env, vault_id);
+
+        let mut returned = Vec::new(&env);
+        for token in storage::get_vault_tokens(&env, vault_id).iter() {
+            let balance = storage::get_balance(&env, vault_id, &token);
+            if balance <= 0 {
+                continue;
+            }
+
+            // Other contributors are refunded first, up to what they put in,
+            // since the owner's own withdrawals never reduced their total.
+            // If the vault no longer covers them, they share it pro-rata.
+            let token_client = token::Client::new(&env, &token);
+            let mut to_owner = balance;
+            if config.refund_on_close {
+                let mut total = 0;
+                for c in contributors.iter() {
+                    if c != vault.owner {
+                        total += storage::get_contribution(&env, vault_id, &c, &token);
+                    }
+                }
+                for c in contributors.iter() {
+                    if c == vault.owner || total == 0 {
+                        continue;
+                    }
+                    let contribution = storage::get_contribution(&env, vault_id, &c, &token);
+                    let share = if total <= balance {
+                        contribution
+                    } else {
+                        balance * contribution / total
+                    };
+                    if share > 0 {
+                        token_client.transfer(&env.current_contract_address(), &c, &share);
+                        to_owner -= share;
+                    }
+                }
+            }
+
+            if to_owner > 0 {
+                // Closing is a withdrawal too and counts against the daily cap.
+                if let Some(limit) = current_withdrawal_limit(&env, vault_id, &token) {
+                    record_withdrawal(&env, vault_id, &token, to_owner, limit.daily_limit)?;
+                }
+                token_client.transfer(&env.current_contract_address(), &vault.owner, &to_owner);
+            }
+            storage::decrease_balance(&env, vault_id, &token, balance);
+            returned.push_back(TokenBalance { token, amount: balance });
+        }
+
+        storage::remove_beneficiaries(&env, vault_id);
+        storage::remove_commitments(&env, vault_id);
+        storage::remove_pending_beneficiaries(&env, vault_id);
+        storage::remove_pending_guardians(&env, vault_id);
+        storage::remove_waterfall(&env, vault_id);
+        storage::remove_pol_link(&env, vault_id);
+        storage::remove_allowances(&env, vault_id);
+        storage::remove_pending_owner(&env, vault_id);
+        storage::remove_joint_owners(&env, vault_id);
+        storage::remove_strategy(&env, vault_id);
+
+        vault.status = VaultStatus::Closed;
+        vault.last_updated = env.ledger().timestamp();
+        storage::set_vault(&env, vault_id, &vault);
+
+        env.events()
+            .publish((symbol_short!("vault"), symbol_short!("closed")), (vault_id, vault.owner));
+
+        Ok(returned)
+    }
+
+    /// Set or clear the dispute period that follows a trigger.
+    pub fn set_dispute_config(
+        env: Env,
+        vault_id: VaultId,
+        config: Option<DisputeConfig>,
+    ) -> Result<(), VaultError> {
+        ensure_writable(&env, vault_id)?;
+
+        let vault = storage::get_vault(&env, vault_id)
+            .ok_or(VaultError::VaultNotFound)?;
+
+        vault.owner.require_auth();
+
+        if vault.status != VaultStatus::Active {
+            return Err(VaultError::InvalidStatus);
+        }
+
+        match &config {
+            Some(c) => {
+                if c.window_secs == 0 || c.window_secs > MAX_DISPUTE_WINDOW {
+                    return Err(VaultError::InvalidDisputeConfig);
+                }
+                storage::set_dispute_config(&env, vault_id, c);
+            }
+            None => storage::remove_dispute_config(&env, vault_id),
+        }
+
+        env.events()
+            .publish((symbol_short!("vault"), symbol_short!("disp_cfg")), (vault_id, config.is_some()));
+
+        Ok(())
+    }
+
+    /// Get the dispute configuration for a vault, if any.
+    pub fn get_dispute_config(env: Env, vault_id: VaultId) -> Result<Option<DisputeConfig>, VaultError> {
+        if storage::get_vault(&env, vault_id).is_none() {
+            return Err(VaultError::VaultNotFound);
+        }
+        Ok(storage::get_dispute_config(&env, vault_id))
+    }
+
+    /// Contest a trigger within the dispute window. Blocks every claim until
+    /// the resolver rules, or until `DISPUTE_RESOLUTION_WINDOW` passes without
+    /// a ruling and the trigger stands. Open to the owner, a guardian or a witness.
+    pub fn open_dispute(
+        env: Env,
+        vault_id: VaultId,
+        caller: Address,
+        evidence: BytesN<32>,
+    ) -> Result<(), VaultError> {
+        ensure_not_paused(&env)?;
+
+        caller.require_auth();
+
+        let vault = storage::get_vault(&env, vault_id)
+            .ok_or(VaultError::VaultNotFound)?;
+
+        if vault.status != VaultStatus::Triggered {
+            return Err(VaultError::InvalidStatus);
+        }
+
+        let config = storage::get_dispute_config(&env, vault_id)
+            .ok_or(VaultError::InvalidDisputeConfig)?;
+        if !may_dispute(&env, &vault, &config, &caller) {
+            return Err(VaultError::NotAuthorized);
+        }
+
+        let now = env.ledger().timestamp();
+        let triggered_at = storage::get_triggered_at(&env, vault_id).unwrap_or(0);
+        if now >= triggered_at + config.window_secs {
+            return Err(VaultError::DisputeWindowClosed);
+        }
+
+        let mut disputes = storage::get_disputes(&env, vault_id);
+        if let Some(last) = disputes.last() {
+            if last.opened_at >= triggered_at {
+                // This trigger has already been disputed.
+                if last.status == DisputeStatus::Open && !dispute_lapsed(&env, &last) {
+                    return Err(VaultError::DisputeOpen);
+                }
+                return Err(VaultError::DisputeWindowClosed);
+            }
+        }
+
+        let mut log = Vec::new(&env);
+        log.push_back(Evidence { submitted_by: caller.clone(), hash: evidence.clone(), timestamp: now });
+        disputes.push_back(Dispute {
+            opened_by: caller.clone(),
+            opened_at: now,
+            evidence: log,
+            status: DisputeStatus::Open,
+            resolved_at: None,
+        });
+        storage::set_disputes(&env, vault_id, &disputes);
+
+        env.events()
+            .publish((symbol_short!("vault"), symbol_short!("disputed")), (vault_id, caller, evidence));
+
+        Ok(())
+    }
+
+    /// Attach another evidence hash to the open dispute.
+    pub fn submit_evidence(
+        env: Env,
+        vault_id: VaultId,
+        caller: Address,
+        evidence: BytesN<32>,
+    ) -> Result<(), VaultError> {
+        ensure_not_paused(&env)?;
+
+        caller.require_auth();
+
+        let vault = storage::get_vault(&env, vault_id)
+            .ok_or(VaultError::VaultNotFound)?;
+
+        let config = storage::get_dispute_config(&env, vault_id)
+            .ok_or(VaultError::InvalidDisputeConfig)?;
+        if !may_dispute(&env, &vault, &config, &caller) && caller != config.resolver {
+            return Err(VaultError::NotAuthorized);
+        }
+
+        let mut disputes = storage::get_disputes(&env, vault_id);
+        let index = disputes.len().checked_sub(1).ok_or(VaultError::NoDispute)?;
+        let mut dispute = disputes.get(index).unwrap();
+        if dispute.status != DisputeStatus::Open {
+            return Err(VaultError::NoDispute);
+        }
+        if dispute_lapsed(&env, &dispute) {
+            return Err(VaultError::DisputeWindowClosed);
+        }
+        if dispute.evidence.len() >= MAX_EVIDENCE {
+            return Err(VaultError::InvalidDisputeConfig);
+        }
+
+        dispute.evidence.push_back(Evidence {
+            submitted_by: caller.clone(),
+            hash: evidence.clone(),
+            timestamp: env.ledger().timestamp(),
+        });
+        disputes.set(index, dispute);
+        storage::set_disputes(&env, vault_id, &disputes);
+
+        env.events()
+            .publish((symbol_short!("vault"), symbol_short!("evidence")), (vault_id, caller, evidence));
+
+        Ok(())
+    }
+
+    /// Rule on the open dispute before its deadline. `RestoreActive` returns
+    /// a still-Triggered vault to Active; `ConfirmTrigger` lets claims proceed
+    /// without waiting out the window.
+    pub fn resolve_dispute(
+        env: Env,
+        vault_id: VaultId,
+        ruling: DisputeRuling,
+    ) -> Result<(), VaultError> {
+        ensure_not_paused(&env)?;
+
+        let mut vault = storage::get_vault(&env, vault_id)
+            .ok_or(VaultError::VaultNotFound)?;
+
+        let config = storage::get_dispute_config(&env, vault_id)
+            .ok_or(VaultError::InvalidDisputeConfig)?;
+        config.resolver.require_auth();
+
+        let mut disputes = storage::get_disputes(&env, vault_id);
+        let index = disputes.len().checked_sub(1).ok_or(VaultError::NoDispute)?;
+        let mut dispute = disputes.get(index).unwrap();
+        if dispute.status != DisputeStatus::Open {
+            return Err(VaultError::NoDispute);
+        }
+        if dispute_lapsed(&env, &dispute) {
+            return Err(VaultError::DisputeWindowClosed);
+        }
+        if ruling == DisputeRuling::RestoreActive && vault.status != VaultStatus::Triggered {
+            return Err(VaultError::InvalidStatus);
+        }
+
+        let now = env.ledger().timestamp();
+        dispute.status = match ruling {
+            DisputeRuling::RestoreActive => DisputeStatus::Restored,
+            DisputeRuling::ConfirmTrigger => DisputeStatus::Confirmed,
+        };
+        dispute.resolved_at = Some(now);
+        disputes.set(index, dispute);
+        storage::set_disputes(&env, vault_id, &disputes);
+
+        if ruling == DisputeRuling::RestoreActive {
+            set_status(&env, &mut vault, VaultStatus::Active);
+        }
+
+        env.events()
+            .publish((symbol_short!("vault"), symbol_short!("ruling")), (vault_id, ruling));
+
+        Ok(())
+    }
+
+    /// Every dispute raised on a vault, oldest first, with its evidence.
+    pub fn get_disputes(env: Env, vault_id: VaultId) -> Result<Vec<Dispute>, VaultError> {
+        if storage::get_vault(&env, vault_id).is_none() {
+            return Err(VaultError::VaultNotFound);
+        }
+        Ok(storage::get_disputes(&env, vault_id))
+    }
+
+    /// Set the platform fees applied to vaults created from now on. Existing
+    /// vaults keep the fees they were created with.
+    pub fn set_fee_config(env: Env, config: FeeConfig) -> Result<(), VaultError> {
+        let admin = storage::get_admin(&env);
+        admin.require_auth();
+
+        if config.deposit_bps > MAX_PLATFORM_FEE_BPS || config.claim_bps > MAX_PLATFORM_FEE_BPS {
+            return Err(VaultError::FeeTooHigh);
+        }
+
+        storage::set_fee_config(&env, &config);
+
+        env.events()
+            .publish((symbol_short!("vault"), symbol_short!("fee_cfg")), (config.deposit_bps, config.claim_bps));
+
+        Ok(())
+    }
+
+    /// Get the fees new vaults will be created with.
+    pub fn get_fee_config(env: Env) -> FeeConfig {
+        storage::get_fee_config(&env)
+    }
+
+    /// Get the fees snapshotted onto a vault at creation.
+    pub fn get_vault_fees(env: Env, vault_id: VaultId) -> Result<FeeConfig, VaultError> {
+        if storage::get_vault(&env, vault_id).is_none() {
+            return Err(VaultError::VaultNotFound);
+        }
+        Ok(storage::get_vault_fees(&env, vault_id))
+    }
+
+    /// Fees accrued in `token` and not yet collected.
+    pub fn get_treasury_balance(env: Env, token: Address) -> i128 {
+        storage::get_treasury(&env, &token)
+    }
+
+    /// Send every accrued fee in `token` to `to`.
+    pub fn collect_fees(env: Env, token: Address, to: Address) -> Result<i128, VaultError> {
+        ensure_not_paused(&env)?;
+
+        let admin = storage::get_admin(&env);
+        admin.require_auth();
+
+        let amount = storage::get_treasury(&env, &token);
+        if amount <= 0 {
+            return Err(VaultError::NothingToClaim);
+        }
+
+        storage::set_treasury(&env, &token, 0);
+        token::Client::new(&env, &token).transfer(&env.current_contract_address(), &to, &amount);
+
+        env.events()
+            .publish((symbol_short!("vault"), symbol_short!("fees_out")), (token, to, amount));
+
+        Ok(amount)
+    }
+
+    /// Compare the contract's actual holding of `token` with its books and
+    /// report the difference. Tokens sent straight to the contract show up
+    /// as a positive surplus that `sweep` can attribute.
+    pub fn sync_balance(env: Env, token: Address) -> BalanceReport {
+        let report = balance_report(&env, &token);
+        if report.surplus != 0 {
+            env.events().publish(
+                (symbol_short!("vault"), symbol_short!("surplus")),
+                (token, report.surplus),
+            );
+        }
+        report
+    }
+
+    /// Credit the untracked surplus of `token` to a vault, or to the fee
+    /// treasury when `vault_id` is `None`. Admin only, since only the admin
+    /// can know who a direct transfer was meant for.
+    pub fn sweep(env: Env, token: Address, vault_id: Option<VaultId>) -> Result<i128, VaultError> {
+        ensure_not_paused(&env)?;
+
+        let admin = storage::get_admin(&env);
+        admin.require_auth();
+
+        let surplus = balance_report(&env, &token).surplus;
+        if surplus <= 0 {
+            return Err(VaultError::NoSurplus);
+        }
+
+        match vault_id {
+            Some(vault_id) => {
+                let vault = storage::get_vault(&env, vault_id)
+                    .ok_or(VaultError::VaultNotFound)?;
+                if vault.status == VaultStatus::Closed {
+                    return Err(VaultError::InvalidStatus);
+                }
+
+                let mut tokens = storage::get_vault_tokens(&env, vault_id);
+                if !tokens.contains(&token) {
+                    if tokens.len() >= MAX_TOKENS {
+                        return Err(VaultError::TooManyTokens);
+                    }
+                    tokens.push_back(token.clone());
+                    storage::set_vault_tokens(&env, vault_id, &tokens);
+                }
+                storage::increase_balance(&env, vault_id, &token, surplus);
+            }
+            None => storage::set_treasury(&env, &token, storage::get_treasury(&env, &token) + surplus),
+        }
+
+        env.events()
+            .publish((symbol_short!("vault"), symbol_short!("sweep")), (token, vault_id, surplus));
+
+        Ok(surplus)
+    }
+
+    /// Freeze a vault for up to `MAX_FREEZE_DURATION`, blocking every write to
+    /// it (claims included) while a suspected fraud is investigated. The
+    /// freeze lapses on its own, and a vault cannot be frozen again until
+    /// `MAX_FREEZE_DURATION` after the previous freeze ended.
+    pub fn freeze_vault(env: Env, vault_id: VaultId, duration_secs: u64) -> Result<u64, VaultError> {
+        let admin = storage::get_admin(&env);
+        admin.require_auth();
+
+        if storage::get_vault(&env, vault_id).is_none() {
+            return Err(VaultError::VaultNotFound);
+        }
+
+        let now = env.ledger().timestamp();
+        if duration_secs == 0 || duration_secs > MAX_FREEZE_DURATION {
+            return Err(VaultError::FreezeNotAllowed);
+        }
+        if storage::get_frozen_until(&env, vault_id).is_some_and(|until| now < until + MAX_FREEZE_DURATION) {
+            return Err(VaultError::FreezeNotAllowed);
+        }
+
+        let until = now + duration_secs;
+        storage::set_frozen_until(&env, vault_id, until);
+
+        env.events()
+            .publish((symbol_short!("vault"), symbol_short!("frozen")), (vault_id, until));
+
+        Ok(until)
+    }
+
+    /// Lift a freeze early.
+    pub fn unfreeze_vault(env: Env, vault_id: VaultId) -> Result<(), VaultError> {
+        let admin = storage::get_admin(&env);
+        admin.require_auth();
+
+        if !is_frozen(&env, vault_id) {
+            return Err(VaultError::InvalidStatus);
+        }
+
+        storage::set_frozen_until(&env, vault_id, env.ledger().timestamp());
+
+        env.events()
+            .publish((symbol_short!("vault"), symbol_short!("unfrozen")), vault_id);
+
+        Ok(())
+    }
+
+    /// When the current freeze on a vault ends, if it is frozen.
+    pub fn get_frozen_until(env: Env, vault_id: VaultId) -> Result<Option<u64>, VaultError> {
+        if storage::get_vault(&env, vault_id).is_none() {
+            return Err(VaultError::VaultNotFound);
+        }
+        Ok(storage::get_frozen_until(&env, vault_id).filter(|_| is_frozen(&env, vault_id)))
+    }
+
+    /// Set the DocumentRegistry whose documents follow a vault's ownership.
+    pub fn set_document_registry(env: Env, registry: Address) -> Result<(), VaultError> {
+        let admin = storage::get_admin(&env);
+        admin.require_auth();
+        storage::set_document_registry(&env, &registry);
+        Ok(())
+    }
+
+    /// Propose `new_owner` as the vault's owner. Takes effect once they call
+    /// `accept_ownership`. Only while Active and the owner's liveness score is
+    /// healthy.
+    pub fn transfer_ownership(
+        env: Env,
+        vault_id: VaultId,
+        new_owner: Address,
+    ) -> Result<(), VaultError> {
+        ensure_writable(&env, vault_id)?;
+
+        let vault = storage::get_vault(&env, vault_id)
+            .ok_or(VaultError::VaultNotFound)?;
+
+        vault.owner.require_auth();
+
+        if vault.status != VaultStatus::Active {
+            return Err(VaultError::InvalidStatus);
+        }
+        ensure_owner_healthy(&env, &vault)?;
+
+        if storage::get_joint_owners(&env, vault_id).contains(&new_owner) {
+            return Err(VaultError::JointOwnerExists);
+        }
+
+        storage::set_pending_owner(&env, vault_id, &new_owner);
+
+        env.events().publish(
+            (symbol_short!("vault"), symbol_short!("own_prop")),
+            (vault_id, vault.owner, new_owner),
+        );
+
+        Ok(())
+    }
+
+    /// Accept a proposed ownership transfer. The owner's ProofOfLife history
+    /// belongs to the person, so every other Active vault they hold moves to
+    /// the new address in the same step, together with each vault's documents.
+    pub fn accept_ownership(env: Env, vault_id: VaultId) -> Result<(), VaultError> {
+        ensure_writable(&env, vault_id)?;
+
+        let vault = storage::get_vault(&env, vault_id)
+            .ok_or(VaultError::VaultNotFound)?;
+
+        let new_owner = storage::get_pending_owner(&env, vault_id)
+            .ok_or(VaultError::NoPendingChange)?;
+        new_owner.require_auth();
+
+        if vault.status != VaultStatus::Active {
+            return Err(VaultError::InvalidStatus);
+        }
+        ensure_owner_healthy(&env, &vault)?;
+
+        let old_owner = vault.owner.clone();
+        let pol = storage::get_pol_link(&env, vault_id)
+            .ok_or(VaultError::ProofOfLifeNotLinked)?;
+
+        // Closed and Distributed vaults stay behind; any other vault must be
+        // Active, so an Alert or Triggered vault is never re-pointed.
+        let mut moving = Vec::from_array(&env, [vault_id]);
+        for other in storage::get_owner_vaults(&env, &old_owner).iter() {
+            if other == vault_id {
+                continue;
+            }
+            let Some(other_vault) = storage::get_vault(&env, other) else {
+                continue;
+            };
+            match other_vault.status {
+                VaultStatus::Closed | VaultStatus::Distributed => continue,
+                VaultStatus::Active => {}
+                _ => return Err(VaultError::InvalidStatus),
+            }
+            ensure_writable(&env, other)?;
+            if storage::get_pol_link(&env, other).is_some_and(|p| p != pol) {
+                return Err(VaultError::MigrationFailed);
+            }
+            moving.push_back(other);
+        }
+
+        let pol_client = ProofOfLifeClient::new(&env, &pol);
+        let linked = match pol_client.try_get_linked_vault_id(&old_owner) {
+            Ok(Ok(Some(linked))) if moving.contains(linked) => linked,
+            _ => return Err(VaultError::MigrationFailed),
+        };
+        match pol_client.try_migrate_user(&old_owner, &new_owner, &linked) {
+            Ok(Ok(())) => {}
+            _ => return Err(VaultError::MigrationFailed),
+        }
+
+        for id in moving.iter() {
+            move_vault(&env, id, &old_owner, &new_owner)?;
+        }
+
+        Ok(())
+    }
+
+    /// Get the proposed new owner of a vault, if any.
+    pub fn get_pending_owner(env: Env, vault_id: VaultId) -> Result<Option<Address>, VaultError> {
+        if storage::get_vault(&env, vault_id).is_none() {
+            return Err(VaultError::VaultNotFound);
+        }
+        Ok(storage::get_pending_owner(&env, vault_id))
+    }
+
+    /// Link a ProofOfLife contract to this vault.
+    pub fn link_proof_of_life(
+        env: Env,
+        vault_id: VaultId,
+        pol_contract: Address,
+    ) -> Result<(), VaultError> {
+        ensure_writable(&env, vault_id)?;
+
+        let vault = storage::get_vault(&env, vault_id)
+            .ok_or(VaultError::VaultNotFound)?;
+
+        vault.owner.require_auth();
+
+        if vault.status == VaultStatus::Closed {
+            return Err(VaultError::InvalidStatus);
+        }
+
+        storage::set_pol_link(&env, vault_id, &pol_contract);
+
+        Ok(())
+    }
+
+    /// Transition vault status. Only callable by the linked ProofOfLife contract or admin.
+    pub fn transition_status(
+        env: Env,
+        vault_id: VaultId,
+        caller: Address,
+        new_status: VaultStatus,
+    ) -> Result<(), VaultError> {
+        caller.require_auth();
+
+        let mut vault = storage::get_vault(&env, vault_id)
+            .ok_or(VaultError::VaultNotFound)?;
+
+        ensure_status_authority(&env, vault_id, &caller)?;
+
+        // Closing goes through `close_vault`, and a closed vault stays closed.
+        if vault.status == VaultStatus::Closed || new_status == VaultStatus::Closed {
+            return Err(VaultError::InvalidStatus);
+        }
+
+        // A joint vault only triggers through per-owner reports.
+        if new_status == VaultStatus::Triggered
+            && !storage::get_joint_owners(&env, vault_id).is_empty()
+        {
+            return Err(VaultError::SurvivorAlive);
+        }
+
+        set_status(&env, &mut vault, new_status);
+
+        Ok(())
+    }
+
+    /// Add a second owner, making this a joint vault. Both owners must sign.
+    /// The primary owner keeps day-to-day control; when either owner dies the
+    /// survivor becomes the sole owner, and only the last death triggers the
+    /// vault.
+    pub fn add_joint_owner(env: Env, vault_id: VaultId, co_owner: Address) -> Result<(), VaultError> {
+        ensure_writable(&env, vault_id)?;
+
+        let vault = storage::get_vault(&env, vault_id)
+            .ok_or(VaultError::VaultNotFound)?;
+
+        vault.owner.require_auth();
+        co_owner.require_auth();
+
+        if vault.status != VaultStatus::Active {
+            return Err(VaultError::InvalidStatus);
+        }
+
+        let mut co_owners = storage::get_joint_owners(&env, vault_id);
+        if !co_owners.is_empty() || co_owner == vault.owner {
+            return Err(VaultError::JointOwnerExists);
+        }
+        co_owners.push_back(co_owner.clone());
+        storage::set_joint_owners(&env, vault_id, &co_owners);
+        storage::add_owner_vault(&env, &co_owner, vault_id);
+
+        env.events()
+            .publish((symbol_short!("vault"), symbol_short!("joint")), (vault_id, co_owner));
+
+        Ok(())
+    }
+
+    /// Every owner of a vault, primary first.
+    pub fn get_owners(env: Env, vault_id: VaultId) -> Result<Vec<Address>, VaultError> {
+        let vault = storage::get_vault(&env, vault_id)
+            .ok_or(VaultError::VaultNotFound)?;
+        Ok(owners_of(&env, &vault))
+    }
+
+    /// Liveness status last reported for one owner of a vault.
+    pub fn get_owner_status(env: Env, vault_id: VaultId, owner: Address) -> Result<VaultStatus, VaultError> {
+        let vault = storage::get_vault(&env, vault_id)
+            .ok_or(VaultError::VaultNotFound)?;
+        if !owners_of(&env, &vault).contains(&owner) {
+            return Err(VaultError::NotAnOwner);
+        }
+        Ok(storage::get_owner_status(&env, vault_id, &owner))
+    }
+
+    /// Report one owner's liveness status. Callable by the admin or the
+    /// linked ProofOfLife contract. An owner reported as Triggered while
+    /// another owner lives is dropped and the survivor becomes sole owner;
+    /// the vault itself follows whichever owner is most alive.
+    pub fn report_owner_status(
+        env: Env,
+        vault_id: VaultId,
+        caller: Address,
+        owner: Address,
+        status: VaultStatus,
+    ) -> Result<(), VaultError> {
+        caller.require_auth();
+
+        let mut vault = storage::get_vault(&env, vault_id)
+            .ok_or(VaultError::VaultNotFound)?;
+
+        ensure_status_authority(&env, vault_id, &caller)?;
+
+        // Only liveness statuses can be reported, and only before the vault
+        // has triggered.
+        if severity(&status) > severity(&VaultStatus::Triggered)
+            || severity(&vault.status) > severity(&VaultStatus::GracePeriod)
+        {
+            return Err(VaultError::InvalidStatus);
+        }
+
+        let mut co_owners = storage::get_joint_owners(&env, vault_id);
+        if owner != vault.owner && !co_owners.contains(&owner) {
+            return Err(VaultError::NotAnOwner);
+        }
+        storage::set_owner_status(&env, vault_id, &owner, &status);
+
+        if status == VaultStatus::Triggered && !co_owners.is_empty() {
+            if owner == vault.owner {
+                vault.owner = co_owners.pop_front().unwrap();
+            } else {
+                co_owners.remove(co_owners.first_index_of(&owner).unwrap());
+            }
+            storage::set_joint_owners(&env, vault_id, &co_owners);
+            storage::remove_owner_status(&env, vault_id, &owner);
+            storage::remove_pending_owner(&env, vault_id);
+            storage::remove_owner_vault(&env, &owner, vault_id);
+
+            env.events().publish(
+                (symbol_short!("vault"), symbol_short!("survivor")),
+                (vault_id, owner, vault.owner.clone()),
+            );
+        }
+
+        let mut new_status = VaultStatus::Triggered;
+        for o in owners_of(&env, &vault).iter() {
+            let s = storage::get_owner_status(&env, vault_id, &o);
+            if severity(&s) < severity(&new_status) {
+                new_status = s;
+            }
+        }
+        set_status(&env, &mut vault, new_status);
+
+        Ok(())
+    }
+}
+
+/// First-time configuration applies immediately; replacing an existing list
+/// is queued behind the beneficiary delay so owners and guardians can cancel.
+#[allow(deprecated)]
+fn queue_or_apply(
+    env: &Env,
+    vault_id: VaultId,
+    beneficiaries: Vec<Beneficiary>,
+    commitments: Vec<BeneficiaryCommitment>,
+) {
+    let configured = !storage::get_beneficiaries(env, vault_id).is_empty()
+        || !storage::get_commitments(env, vault_id).is_empty();
+
+    if !configured {
+        apply_beneficiaries(env, vault_id, beneficiaries, commitments);
+        return;
+    }
+
+    let now = env.ledger().timestamp();
+    let pending = PendingBeneficiaries {
+        beneficiaries,
+        commitments,
+        queued_at: now,
+        executable_at: now + storage::get_beneficiary_delay(env),
+    };
+    storage::set_pending_beneficiaries(env, vault_id, &pending);
+
+    env.events().publish(
+        (symbol_short!("vault"), symbol_short!("bqueued")),
+        (vault_id, pending.executable_at),
+    );
+}
+
+/// Waterfall changes divert the estate as surely as a beneficiary change, so
+/// they apply immediately only while the vault has no payout configuration
+/// at all; otherwise they wait out the beneficiary delay, replacing any
+/// queued change of the same kind.
+#[allow(deprecated)]
+fn queue_or_apply_waterfall(env: &Env, vault_id: VaultId, change: WaterfallChange) {
+    let configured = !storage::get_beneficiaries(env, vault_id).is_empty()
+        || !storage::get_commitments(env, vault_id).is_empty()
+        || !storage::get_bequests(env, vault_id).is_empty()
+        || storage::get_executor(env, vault_id).is_some()
+        || !storage::get_creditors(env, vault_id).is_empty();
+
+    if !configured {
+        apply_waterfall(env, vault_id, change);
+        return;
+    }
+
+    let now = env.ledger().timestamp();
+    let entry = PendingWaterfall {
+        change,
+        queued_at: now,
+        executable_at: now + storage::get_beneficiary_delay(env),
+    };
+
+    let mut pending = Vec::new(env);
+    for p in storage::get_pending_waterfall(env, vault_id).iter() {
+        if !same_kind(&p.change, &entry.change) {
+            pending.push_back(p);
+        }
+    }
+    pending.push_back(entry.clone());
+    storage::set_pending_waterfall(env, vault_id, &pending);
+
+    env.events().publish(
+        (symbol_short!("vault"), symbol_short!("wqueued")),
+        (vault_id, entry.executable_at),
+    );
+}
+
+fn same_kind(a: &WaterfallChange, b: &WaterfallChange) -> bool {
+    matches!(
+        (a, b),
+        (WaterfallChange::Bequests(_), WaterfallChange::Bequests(_))
+            | (WaterfallChange::Executor(_), WaterfallChange::Executor(_))
+            | (WaterfallChange::Creditors(_), WaterfallChange::Creditors(_))
+    )
+}
+
+#[allow(deprecated)]
+fn apply_waterfall(env: &Env, vault_id: VaultId, change: WaterfallChange) {
+    match change {
+        WaterfallChange::Bequests(bequests) => {
+            storage::set_bequests(env, vault_id, &bequests);
+            env.events()
+                .publish((symbol_short!("vault"), symbol_short!("bequests")), vault_id);
+        }
+        WaterfallChange::Executor(fee) => {
+            storage::set_executor(env, vault_id, &fee);
+            env.events().publish(
+                (symbol_short!("vault"), symbol_short!("executor")),
+                (vault_id, fee.executor, fee.fee_bps),
+            );
+        }
+        WaterfallChange::Creditors(creditors) => {
+            storage::set_creditors(env, vault_id, &creditors);
+            env.events()
+                .publish((symbol_short!("vault"), symbol_short!("debts")), vault_id);
+        }
+    }
+}
+
+#[allow(deprecated)]
+fn apply_beneficiaries(
+    env: &Env,
+    vault_id: VaultId,
+    beneficiaries: Vec<Beneficiary>,
+    commitments: Vec<BeneficiaryCommitment>,
+) {
+    if commitments.is_empty() {
+        storage::set_beneficiaries(env, vault_id, &beneficiaries);
+        storage::remove_commitments(env, vault_id);
+        env.events()
+            .publish((symbol_short!("vault"), symbol_short!("benef")), vault_id);
+    } else {
+        storage::set_commitments(env, vault_id, &commitments);
+        storage::remove_beneficiaries(env, vault_id);
+        env.events()
+            .publish((symbol_short!("vault"), symbol_short!("commit")), vault_id);
+    }
+}
+
+/// `sha256(address_xdr || salt)`, the preimage a hidden beneficiary reveals at claim time.
+fn commitment_of(env: &Env, beneficiary: &Address, salt: &BytesN<32>) -> BytesN<32> {
+    let mut preimage = beneficiary.clone().to_xdr(env);
+    preimage.extend_from_array(&salt.to_array());
+    env.crypto().sha256(&preimage).into()
+}
+
+/// Credit `fee` taken from a vault's funds to the treasury.
+#[allow(deprecated)]
+fn accrue_fee(env: &Env, vault_id: VaultId, token: &Address, fee: i128) {
+    storage::set_treasury(env, token, storage::get_treasury(env, token) + fee);
+
+    env.events()
+        .publish((symbol_short!("vault"), symbol_short!("fee")), (vault_id, token.clone(), fee));
+}
+
+fn balance_report(env: &Env, token: &Address) -> BalanceReport {
+    let actual = token::Client::new(env, token).balance(&env.current_contract_address());
+    let tracked = storage::get_total_balance(env, token) - storage::get_total_invested(env, token)
+        + storage::get_treasury(env, token);
+    BalanceReport { token: token.clone(), actual, tracked, surplus: actual - tracked }
+}
+
+/// Pay `amount` of `token` out of a vault, keeping the vault's claim fee in
+/// the treasury. Returns what the recipient actually received.
+fn pay_out(env: &Env, vault_id: VaultId, token: &Address, to: &Address, amount: i128) -> i128 {
+    if amount <= 0 {
+        return 0;
+    }
+
+    let fee = amount * storage::get_vault_fees(env, vault_id).claim_bps as i128 / 10_000;
+    if fee > 0 {
+        accrue_fee(env, vault_id, token, fee);
+    }
+
+    let received = amount - fee;
+    token::Client::new(env, token).transfer(&env.current_contract_address(), to, &received);
+    storage::decrease_balance(env, vault_id, token, amount);
+    received
+}
+
+/// Freeze the distribution at the first claim: allocate bequests in list
+/// order against each token balance, then snapshot what remains as the
+/// residue shared by percentage.
+fn settle(env: &Env, vault_id: VaultId) {
+    if storage::is_settled(env, vault_id) {
+        return;
+    }
+
+    let mut remaining: Map<Address, i128> = Map::new(env);
+    for token in storage::get_vault_tokens(env, vault_id).iter() {
+        let balance = storage::get_balance(env, vault_id, &token);
+        remaining.set(token, balance);
+    }
+
+    let mut bequests = storage::get_bequests(env, vault_id);
+    allocate_bequests(&mut bequests, &mut remaining);
+    storage::set_bequests(env, vault_id, &bequests);
+
+    for (token, residue) in remaining.iter() {
+        storage::set_distribution_base(env, vault_id, &token, residue);
+    }
+    storage::set_settled(env, vault_id);
+}
+
+/// Allocate bequests in list order against `remaining`, writing each
+/// bequest's allocated amount back into it.
+fn allocate_bequests(bequests: &mut Vec<Bequest>, remaining: &mut Map<Address, i128>) {
+    for i in 0..bequests.len() {
+        let mut b = bequests.get(i).unwrap();
+        let available = remaining.get(b.token.clone()).unwrap_or(0);
+        b.amount = match b.kind {
+            BequestKind::Fixed => b.amount.min(available),
+            BequestKind::WholeToken => available,
+        };
+        remaining.set(b.token.clone(), available - b.amount);
+        bequests.set(i, b);
+    }
+}
+
+/// Amount creditor `index` can be paid now: its claim, capped by what is left
+/// of the token after unpaid creditors ahead of it in the list.
+fn creditor_allocation(env: &Env, vault_id: VaultId, creditors: &Vec<CreditorClaim>, index: u32) -> i128 {
+    let claim = creditors.get(index).unwrap();
+    let mut available = storage::get_balance(env, vault_id, &claim.token);
+    for c in creditors.iter().take(index as usize) {
+        if !c.paid && c.token == claim.token {
+            available -= c.amount.min(available);
+        }
+    }
+    claim.amount.min(available)
+}
+
+/// The withdrawal allowlist without entries whose removal has taken effect.
+fn live_withdrawal_addresses(env: &Env, vault_id: VaultId) -> Vec<WithdrawalAddress> {
+    let now = env.ledger().timestamp();
+    let mut live = Vec::new(env);
+    for a in storage::get_withdrawal_addresses(env, vault_id).iter() {
+        if a.removed_at == 0 || now < a.removed_at {
+            live.push_back(a);
+        }
+    }
+    live
+}
+
+/// Once a vault has an allowlist, withdrawals may only go to the owner or to
+/// an entry whose timelock has passed.
+fn ensure_destination_allowed(env: &Env, vault: &VaultInfo, to: &Address) -> Result<(), VaultError> {
+    let addresses = live_withdrawal_addresses(env, vault.id);
+    if addresses.is_empty() || *to == vault.owner {
+        return Ok(());
+    }
+
+    let now = env.ledger().timestamp();
+    if addresses.iter().any(|a| a.address == *to && a.active_from <= now) {
+        Ok(())
+    } else {
+        Err(VaultError::DestinationNotAllowed)
+    }
+}
+
+/// Count `amount` against the rolling 24h window for `token`, failing if it
+/// would take the total past `cap`.
+/// The withdrawal limit in force for a token, including a queued change
+/// whose delay has passed.
+fn current_withdrawal_limit(env: &Env, vault_id: VaultId, token: &Address) -> Option<WithdrawalLimit> {
+    match storage::get_pending_withdrawal_limit(env, vault_id, token) {
+        Some(p) if env.ledger().timestamp() >= p.executable_at => (p.daily_limit > 0)
+            .then_some(WithdrawalLimit { daily_limit: p.daily_limit, alert_limit: p.alert_limit }),
+        _ => storage::get_withdrawal_limit(env, vault_id, token),
+    }
+}
+
+fn record_withdrawal(
+    env: &Env,
+    vault_id: VaultId,
+    token: &Address,
+    amount: i128,
+    cap: i128,
+) -> Result<(), VaultError> {
+    let now = env.ledger().timestamp();
+    let mut recent = Vec::new(env);
+    let mut spent = 0;
+    for r in storage::get_recent_withdrawals(env, vault_id, token).iter() {
+        if r.timestamp + WITHDRAWAL_WINDOW > now {
+            spent += r.amount;
+            recent.push_back(r);
+        }
+    }
+
+    if spent + amount > cap {
+        return Err(VaultError::LimitExceeded);
+    }
+
+    recent.push_back(WithdrawalRecord { timestamp: now, amount });
+    storage::set_recent_withdrawals(env, vault_id, token, &recent);
+    Ok(())
+}
+
+/// Status changes come from the admin or the vault's linked ProofOfLife.
+/// While paused or frozen only the admin may move a vault's status.
+fn ensure_status_authority(env: &Env, vault_id: VaultId, caller: &Address) -> Result<(), VaultError> {
+    let is_admin = *caller == storage::get_admin(env);
+    let is_pol = storage::get_pol_link(env, vault_id).is_some_and(|pol| *caller == pol);
+
+    if !is_admin && !is_pol {
+        return Err(VaultError::NotAuthorized);
+    }
+    if !is_admin {
+        ensure_writable(env, vault_id)?;
+    }
+    Ok(())
+}
+
+/// Move a vault to `new_status`, applying the side effects of leaving Active
+/// and of entering Triggered.
+#[allow(deprecated)]
+fn set_status(env: &Env, vault: &mut VaultInfo, new_status: VaultStatus) {
+    let vault_id = vault.id;
+
+    // A queued beneficiary, waterfall or guardian change must never survive leaving Active.
+    if new_status != VaultStatus::Active {
+        storage::remove_pending_beneficiaries(env, vault_id);
+        storage::remove_pending_waterfall(env, vault_id);
+        storage::remove_pending_guardians(env, vault_id);
+        storage::remove_pending_owner(env, vault_id);
+    }
+
+    if new_status == VaultStatus::Triggered && vault.status != VaultStatus::Triggered {
+        storage::set_triggered_at(env, vault_id, env.ledger().timestamp());
+        // Living allowances end once the vault switches to inheritance.
+        storage::remove_allowances(env, vault_id);
+        // Bring invested funds home for the heirs. A failing strategy must not
+        // block the trigger; its position can be recalled later with `divest`.
+        for token in storage::get_vault_tokens(env, vault_id).iter() {
+            let _ = recall(env, vault_id, &token);
+        }
+    }
+
+    vault.status = new_status.clone();
+    vault.last_updated = env.ledger().timestamp();
+    storage::set_vault(env, vault_id, vault);
+
+    env.events()
+        .publish((symbol_short!("vault"), symbol_short!("status")), (vault_id, new_status));
+}
+
+/// Withdraw a token's whole strategy position back into the contract and
+/// return the amount recovered. The difference from the invested principal
+/// is booked to the vault balance as yield or loss.
+#[allow(deprecated)]
+fn recall(env: &Env, vault_id: VaultId, token: &Address) -> Result<i128, VaultError> {
+    let invested = storage::get_invested(env, vault_id, token);
+    if invested == 0 {
+        return Ok(0);
+    }
+    let strategy = storage::get_strategy(env, vault_id)
+        .ok_or(VaultError::StrategyNotSet)?;
+
+    let this = env.current_contract_address();
+    let client = StrategyClient::new(env, &strategy);
+    let value = match client.try_total_value(&this, &vault_id, token) {
+        Ok(Ok(value)) => value,
+        _ => return Err(VaultError::StrategyFailed),
+    };
+    if value < 0 {
+        return Err(VaultError::StrategyFailed);
+    }
+
+    // Book only what actually arrived: the strategy's own report is not
+    // trusted, or a lying strategy could mint balance out of other vaults.
+    let token_client = token::Client::new(env, token);
+    let before = token_client.balance(&this);
+    if value > 0 {
+        match client.try_withdraw(&this, &vault_id, token, &value) {
+            Ok(Ok(())) => {}
+            _ => return Err(VaultError::StrategyFailed),
+        }
+    }
+    let received = token_client.balance(&this) - before;
+    if received < value {
+        return Err(VaultError::StrategyFailed);
+    }
+
+    if received > invested {
+        storage::increase_balance(env, vault_id, token, received - invested);
+    } else {
+        storage::decrease_balance(env, vault_id, token, invested - received);
+    }
+    storage::set_invested(env, vault_id, token, 0);
+
+    env.events()
+        .publish((symbol_short!("vault"), symbol_short!("divest")), (vault_id, token.clone(), received));
+
+    Ok(received)
+}
+
+/// Recall every strategy position before paying heirs or priority claims,
+/// so payouts and the residue snapshot only see funds the contract holds.
+fn recall_all(env: &Env, vault_id: VaultId) -> Result<(), VaultError> {
+    for token in storage::get_vault_tokens(env, vault_id).iter() {
+        recall(env, vault_id, &token)?;
+    }
+    Ok(())
+}
+
+/// Liveness statuses in order of escalation.
+fn severity(status: &VaultStatus) -> u32 {
+    match status {
+        VaultStatus::Active => 0,
+        VaultStatus::Alert => 1,
+        VaultStatus::GracePeriod => 2,
+        VaultStatus::Triggered => 3,
+        VaultStatus::Distributed => 4,
+        VaultStatus::Closed => 5,
+    }
+}
+
+fn owners_of(env: &Env, vault: &VaultInfo) -> Vec<Address> {
+    let mut owners = Vec::from_array(env, [vault.owner.clone()]);
+    owners.append(&storage::get_joint_owners(env, vault.id));
+    owners
+}
+
+/// Re-point one vault from `old_owner` to `new_owner`: its documents, the
+/// old owner's contributions, the owner index and any pending proposal.
+#[allow(deprecated)]
+fn move_vault(env: &Env, vault_id: VaultId, old_owner: &Address, new_owner: &Address) -> Result<(), VaultError> {
+    let mut vault = storage::get_vault(env, vault_id)
+        .ok_or(VaultError::VaultNotFound)?;
+
+    if let Some(registry) = storage::get_document_registry(env) {
+        let result = DocumentRegistryClient::new(env, &registry)
+            .try_transfer_vault_documents(&vault_id, old_owner, new_owner);
+        if !matches!(result, Ok(Ok(_))) {
+            return Err(VaultError::MigrationFailed);
+        }
+    }
+
+    // The old owner's deposits now count as the new owner's.
+    let mut contributors = storage::get_contributors(env, vault_id);
+    if let Some(index) = contributors.first_index_of(old_owner) {
+        for token in storage::get_vault_tokens(env, vault_id).iter() {
+            let amount = storage::get_contribution(env, vault_id, old_owner, &token)
+                + storage::get_contribution(env, vault_id, new_owner, &token);
+            storage::set_contribution(env, vault_id, new_owner, &token, amount);
+            storage::set_contribution(env, vault_id, old_owner, &token, 0);
+        }
+        contributors.remove(index);
+        if !contributors.contains(new_owner) {
+            contributors.push_back(new_owner.clone());
+        }
+        storage::set_contributors(env, vault_id, &contributors);
+    }
+
+    vault.owner = new_owner.clone();
+    vault.last_updated = env.ledger().timestamp();
+    storage::set_vault(env, vault_id, &vault);
+    storage::remove_pending_owner(env, vault_id);
+    storage::remove_owner_vault(env, old_owner, vault_id);
+    storage::add_owner_vault(env, new_owner, vault_id);
+
+    env.events().publish(
+        (symbol_short!("vault"), symbol_short!("owner")),
+        (vault_id, old_owner.clone(), new_owner.clone()),
+    );
+
+    Ok(())
+}
+
+/// Ownership may only move while the linked ProofOfLife reports the current
+/// owner as healthy.
+fn ensure_owner_healthy(env: &Env, vault: &VaultInfo) -> Result<(), VaultError> {
+    let pol = storage::get_pol_link(env, vault.id)
+        .ok_or(VaultError::ProofOfLifeNotLinked)?;
+
+    match ProofOfLifeClient::new(env, &pol).try_is_healthy(&vault.owner) {
+        Ok(Ok(true)) => Ok(()),
+        _ => Err(VaultError::OwnerUnhealthy),
+    }
+}
+
+/// The owner, a guardian or a configured witness may contest a trigger.
+fn may_dispute(env: &Env, vault: &VaultInfo, config: &DisputeConfig, caller: &Address) -> bool {
+    *caller == vault.owner
+        || config.witnesses.contains(caller)
+        || storage::get_guardians(env, vault.id).contains(caller)
+}
+
+/// With a dispute period configured, claims wait until the window has passed
+/// or the resolver has confirmed the trigger, and never run while a dispute
+/// is open.
+fn ensure_claims_open(env: &Env, vault_id: VaultId) -> Result<(), VaultError> {
+    let config = match storage::get_dispute_config(env, vault_id) {
+        Some(config) => config,
+        None => return Ok(()),
+    };
+    let triggered_at = storage::get_triggered_at(env, vault_id).unwrap_or(0);
+
+    if let Some(last) = storage::get_disputes(env, vault_id).last() {
+        let current = last.opened_at >= triggered_at;
+        match last.status {
+            // Left unresolved past its deadline, the trigger stands.
+            DisputeStatus::Open if current && dispute_lapsed(env, &last) => return Ok(()),
+            DisputeStatus::Open if current => return Err(VaultError::DisputeOpen),
+            DisputeStatus::Confirmed if current => return Ok(()),
+            _ => {}
+        }
+    }
+
+    if env.ledger().timestamp() < triggered_at + config.window_secs {
+        return Err(VaultError::DisputeWindowOpen);
+    }
+    Ok(())
+}
+
+/// An open dispute the resolver failed to rule on in time.
+fn dispute_lapsed(env: &Env, dispute: &Dispute) -> bool {
+    dispute.status == DisputeStatus::Open
+        && env.ledger().timestamp() >= dispute.opened_at + DISPUTE_RESOLUTION_WINDOW
+}
+
+/// Bequests and percentage shares open only once the executor fee and every
+/// creditor claim has been paid or released.
+fn ensure_heirs_open(env: &Env, vault_id: VaultId) -> Result<(), VaultError> {
+    if priority_lapsed(env, vault_id) {
+        return Ok(());
+    }
+    let executor_pending = storage::get_executor(env, vault_id).is_some_and(|e| !e.paid);
+    let creditors_pending = storage::get_creditors(env, vault_id).iter().any(|c| !c.paid);
+    if executor_pending || creditors_pending {
+        return Err(VaultError::PriorTierPending);
+    }
+    Ok(())
+}
+
+/// Whether the executor and creditor claims have lapsed, treating whatever
+/// they left unpaid as released to the heirs.
+fn priority_lapsed(env: &Env, vault_id: VaultId) -> bool {
+    storage::get_triggered_at(env, vault_id)
+        .is_some_and(|t| env.ledger().timestamp() >= t + PRIORITY_CLAIM_WINDOW)
+}
+
+/// Amount of `token` still owed to unpaid bequests.
+fn outstanding_bequests(env: &Env, vault_id: VaultId, token: &Address) -> i128 {
+    let mut total = 0;
+    for b in storage::get_bequests(env, vault_id).iter() {
+        if !b.paid && b.token == *token {
+            total += b.amount;
+        }
+    }
+    total
+}
+
+/// Move the vault to Distributed once every tier of the waterfall is paid.
+fn mark_if_distributed(env: &Env, vault: &mut VaultInfo) {
+    let vault_id = vault.id;
+    let lapsed = priority_lapsed(env, vault_id);
+    let done = (lapsed || storage::get_executor(env, vault_id).is_none_or(|e| e.paid))
+        && (lapsed || storage::get_creditors(env, vault_id).iter().all(|c| c.paid))
+        && storage::get_bequests(env, vault_id).iter().all(|b| b.paid)
+        && storage::get_beneficiaries(env, vault_id).iter().all(|b| b.claimed)
+        && storage::get_commitments(env, vault_id).iter().all(|c| c.claimed);
+
+    if done {
+        vault.status = VaultStatus::Distributed;
+    }
+    vault.last_updated = env.ledger().timestamp();
+    storage::set_vault(env, vault_id, vault);
+}
+
+/// Pay `percentage` of each token's residue. The last residue claimant
+/// receives whatever residue remains so no rounding dust is left behind.
+#[allow(deprecated)]
+fn pay_share(
+    env: &Env,
+    vault: &mut VaultInfo,
+    percentage: u32,
+    is_last: bool,
+    to: &Address,
+) -> Vec<TokenBalance> {
+    let mut paid = Vec::new(env);
+
+    for token in storage::get_vault_tokens(env, vault.id).iter() {
+        let available = storage::get_balance(env, vault.id, &token)
+            - outstanding_bequests(env, vault.id, &token);
+        let base = storage::get_distribution_base(env, vault.id, &token).unwrap_or(0);
+
+        let amount = if is_last {
+            available
+        } else {
+            (base * percentage as i128 / 10_000).min(available)
+        };
+
+        let received = pay_out(env, vault.id, &token, to, amount);
+
+        env.events().publish(
+            (symbol_short!("vault"), symbol_short!("claim")),
+            (vault.id, to.clone(), token.clone(), received),
+        );
+        paid.push_back(TokenBalance { token, amount: received });
+    }
+
+    mark_if_distributed(env, vault);
+
+    paid
+}
+
+/// Reject state-changing calls while the admin has paused the contract.
+fn ensure_not_paused(env: &Env) -> Result<(), VaultError> {
+    if storage::is_paused(env) {
+        return Err(VaultError::Paused);
+    }
+    Ok(())
+}
+
+fn is_frozen(env: &Env, vault_id: VaultId) -> bool {
+    storage::get_frozen_until(env, vault_id).is_some_and(|until| env.ledger().timestamp() < until)
+}
+
+/// Reject writes to a vault while the contract is paused or the vault is frozen.
+fn ensure_writable(env: &Env, vault_id: VaultId) -> Result<(), VaultError> {
+    ensure_not_paused(env)?;
+    if is_frozen(env, vault_id) {
+        return Err(VaultError::VaultFrozen);
+    }
+    Ok(())
 }
@@ -14,4 +14,43 @@ pub enum VaultError {
     AlreadyInitialized = 9,
     NotInitialized = 10,
     ProofOfLifeNotLinked = 11,
+    NotABeneficiary = 12,
+    AlreadyClaimed = 13,
+    InvalidCommitment = 14,
+    NoPendingChange = 15,
+    TimelockActive = 16,
+    TooManyTokens = 17,
+    InvalidBequests = 18,
+    OverAllocated = 19,
+    NothingToClaim = 20,
+    FeeTooHigh = 21,
+    PriorTierPending = 22,
+    InvalidCreditors = 23,
+    TooManyContributors = 24,
+    OwnerUnhealthy = 25,
+    MigrationFailed = 26,
+    DestinationNotAllowed = 27,
+    LimitExceeded = 28,
+    InvalidLimit = 29,
+    TooManyAddresses = 30,
+    Paused = 31,
+    VaultFrozen = 32,
+    FreezeNotAllowed = 33,
+    InvalidDisputeConfig = 34,
+    DisputeWindowOpen = 35,
+    DisputeWindowClosed = 36,
+    DisputeOpen = 37,
+    NoDispute = 38,
+    NotDue = 39,
+    AllowanceNotFound = 40,
+    TooManyAllowances = 41,
+    InvalidSchedule = 42,
+    JointOwnerExists = 43,
+    NotAnOwner = 44,
+    SurvivorAlive = 45,
+    StrategyNotSet = 46,
+    StrategyFailed = 47,
+    StrategyInUse = 48,
+    NoSurplus = 49,
+    PriorityClaimLapsed = 50,
 }
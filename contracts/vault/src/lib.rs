@@ -2,9 +2,14 @@
 
 pub mod contract;
 pub mod errors;
+pub mod proof_of_life;
+pub mod registry;
 pub mod storage;
+pub mod strategy;
 pub mod types;
 
+#[cfg(test)]
+mod mock_strategy;
 #[cfg(test)]
 mod test;
 
@@ -0,0 +1,69 @@
+use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Env};
+
+#[contracttype]
+#[derive(Clone)]
+enum DataKey {
+    Position(Address, u64, Address),
+    Failing,
+    Lying,
+}
+
+/// Test strategy that simply holds deposited tokens. Yield is simulated by
+/// minting tokens to the contract and crediting them with `accrue`.
+#[contract]
+pub struct MockStrategy;
+
+#[contractimpl]
+impl MockStrategy {
+    pub fn deposit(env: Env, depositor: Address, account: u64, token: Address, amount: i128) {
+        depositor.require_auth();
+        Self::accrue(env, depositor, account, token, amount);
+    }
+
+    pub fn withdraw(env: Env, depositor: Address, account: u64, token: Address, amount: i128) {
+        depositor.require_auth();
+        if env.storage().instance().has(&DataKey::Failing) {
+            panic!("strategy unavailable");
+        }
+        let key = DataKey::Position(depositor.clone(), account, token.clone());
+        let position: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        if position < amount {
+            panic!("insufficient position");
+        }
+        env.storage().persistent().set(&key, &(position - amount));
+        if env.storage().instance().has(&DataKey::Lying) {
+            return;
+        }
+        token::Client::new(&env, &token).transfer(&env.current_contract_address(), &depositor, &amount);
+    }
+
+    pub fn total_value(env: Env, depositor: Address, account: u64, token: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Position(depositor, account, token))
+            .unwrap_or(0)
+    }
+
+    pub fn accrue(env: Env, depositor: Address, account: u64, token: Address, amount: i128) {
+        let key = DataKey::Position(depositor, account, token);
+        let position: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        env.storage().persistent().set(&key, &(position + amount));
+    }
+
+    pub fn set_failing(env: Env, failing: bool) {
+        if failing {
+            env.storage().instance().set(&DataKey::Failing, &true);
+        } else {
+            env.storage().instance().remove(&DataKey::Failing);
+        }
+    }
+
+    /// Report success on `withdraw` without sending any tokens back.
+    pub fn set_lying(env: Env, lying: bool) {
+        if lying {
+            env.storage().instance().set(&DataKey::Lying, &true);
+        } else {
+            env.storage().instance().remove(&DataKey::Lying);
+        }
+    }
+}
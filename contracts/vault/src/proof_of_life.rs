@@ -0,0 +1,9 @@
+use soroban_sdk::{contractclient, Address, Env};
+
+/// The subset of the ProofOfLife contract interface the vault depends on.
+#[contractclient(name = "ProofOfLifeClient")]
+pub trait ProofOfLifeInterface {
+    fn is_healthy(env: Env, user: Address) -> bool;
+    fn migrate_user(env: Env, old_user: Address, new_user: Address, vault_id: u64);
+    fn get_linked_vault_id(env: Env, user: Address) -> Option<u64>;
+}
@@ -0,0 +1,7 @@
+use soroban_sdk::{contractclient, Address, Env};
+
+/// The subset of the DocumentRegistry contract interface the vault depends on.
+#[contractclient(name = "DocumentRegistryClient")]
+pub trait DocumentRegistryInterface {
+    fn transfer_vault_documents(env: Env, vault_id: u64, old_owner: Address, new_owner: Address) -> u32;
+}
@@ -1,6 +1,17 @@
 use soroban_sdk::{contracttype, Address, Env};
 
-use crate::types::{VaultId, VaultInfo, Beneficiary};
+use crate::types::{
+    Allowance, Bequest, Beneficiary, BeneficiaryCommitment, CreditorClaim, DepositConfig,
+    Dispute, DisputeConfig, ExecutorFee, FeeConfig, PendingBeneficiaries, PendingGuardians, PendingWaterfall,
+    PendingWithdrawalLimit,
+    VaultId, VaultInfo, VaultStatus,
+    WithdrawalAddress, WithdrawalLimit, WithdrawalRecord,
+};
+
+/// Default delay before a beneficiary change can be executed (7 days).
+pub const DEFAULT_BENEFICIARY_DELAY: u64 = 7 * 24 * 60 * 60;
+/// The admin cannot shorten the beneficiary delay below this (1 day).
+pub const MIN_BENEFICIARY_DELAY: u64 = 24 * 60 * 60;
 
 #[contracttype]
 #[derive(Clone)]
@@ -11,6 +22,44 @@ pub enum DataKey {
     VaultBeneficiaries(VaultId),
     ProofOfLifeLink(VaultId),
     Initialized,
+    Paused,
+    VaultCommitments(VaultId),
+    DistributionBase(VaultId, Address),
+    PendingBeneficiaries(VaultId),
+    PendingWaterfall(VaultId),
+    Guardians(VaultId),
+    PendingGuardians(VaultId),
+    BeneficiaryDelay,
+    TriggeredAt(VaultId),
+    Balance(VaultId, Address),
+    VaultTokens(VaultId),
+    Bequests(VaultId),
+    Settled(VaultId),
+    Executor(VaultId),
+    Creditors(VaultId),
+    DepositConfig(VaultId),
+    Contributors(VaultId),
+    Contribution(VaultId, Address, Address),
+    DocumentRegistry,
+    PendingOwner(VaultId),
+    WithdrawalAddresses(VaultId),
+    WithdrawalLimit(VaultId, Address),
+    PendingWithdrawalLimit(VaultId, Address),
+    RecentWithdrawals(VaultId, Address),
+    FrozenUntil(VaultId),
+    DisputeConfig(VaultId),
+    Disputes(VaultId),
+    FeeConfig,
+    VaultFees(VaultId),
+    Treasury(Address),
+    Allowances(VaultId),
+    JointOwners(VaultId),
+    OwnerStatus(VaultId, Address),
+    Strategy(VaultId),
+    Invested(VaultId, Address),
+    TotalBalance(Address),
+    TotalInvested(Address),
+    OwnerVaults(Address),
 }
 
 pub fn is_initialized(env: &Env) -> bool {
@@ -21,6 +70,14 @@ pub fn set_initialized(env: &Env) {
     env.storage().instance().set(&DataKey::Initialized, &true);
 }
 
+pub fn is_paused(env: &Env) -> bool {
+    env.storage().instance().get(&DataKey::Paused).unwrap_or(false)
+}
+
+pub fn set_paused(env: &Env, paused: bool) {
+    env.storage().instance().set(&DataKey::Paused, &paused);
+}
+
 pub fn get_admin(env: &Env) -> Address {
     env.storage().instance().get(&DataKey::Admin).unwrap()
 }
@@ -67,3 +124,588 @@ pub fn set_pol_link(env: &Env, vault_id: VaultId, pol_contract: &Address) {
         .persistent()
         .set(&DataKey::ProofOfLifeLink(vault_id), pol_contract);
 }
+
+pub fn get_commitments(env: &Env, vault_id: VaultId) -> soroban_sdk::Vec<BeneficiaryCommitment> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::VaultCommitments(vault_id))
+        .unwrap_or(soroban_sdk::Vec::new(env))
+}
+
+pub fn set_commitments(
+    env: &Env,
+    vault_id: VaultId,
+    commitments: &soroban_sdk::Vec<BeneficiaryCommitment>,
+) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::VaultCommitments(vault_id), commitments);
+}
+
+pub fn remove_commitments(env: &Env, vault_id: VaultId) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::VaultCommitments(vault_id));
+}
+
+pub fn remove_pol_link(env: &Env, vault_id: VaultId) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::ProofOfLifeLink(vault_id));
+}
+
+pub fn remove_beneficiaries(env: &Env, vault_id: VaultId) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::VaultBeneficiaries(vault_id));
+}
+
+pub fn get_distribution_base(env: &Env, vault_id: VaultId, token: &Address) -> Option<i128> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::DistributionBase(vault_id, token.clone()))
+}
+
+pub fn set_distribution_base(env: &Env, vault_id: VaultId, token: &Address, base: i128) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::DistributionBase(vault_id, token.clone()), &base);
+}
+
+pub fn get_beneficiary_delay(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&DataKey::BeneficiaryDelay)
+        .unwrap_or(DEFAULT_BENEFICIARY_DELAY)
+}
+
+pub fn set_beneficiary_delay(env: &Env, delay_secs: u64) {
+    env.storage().instance().set(&DataKey::BeneficiaryDelay, &delay_secs);
+}
+
+pub fn get_pending_beneficiaries(env: &Env, vault_id: VaultId) -> Option<PendingBeneficiaries> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::PendingBeneficiaries(vault_id))
+}
+
+pub fn set_pending_beneficiaries(env: &Env, vault_id: VaultId, pending: &PendingBeneficiaries) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::PendingBeneficiaries(vault_id), pending);
+}
+
+pub fn remove_pending_beneficiaries(env: &Env, vault_id: VaultId) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::PendingBeneficiaries(vault_id));
+}
+
+pub fn get_pending_waterfall(env: &Env, vault_id: VaultId) -> soroban_sdk::Vec<PendingWaterfall> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::PendingWaterfall(vault_id))
+        .unwrap_or(soroban_sdk::Vec::new(env))
+}
+
+pub fn set_pending_waterfall(env: &Env, vault_id: VaultId, pending: &soroban_sdk::Vec<PendingWaterfall>) {
+    let key = DataKey::PendingWaterfall(vault_id);
+    if pending.is_empty() {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, pending);
+    }
+}
+
+pub fn remove_pending_waterfall(env: &Env, vault_id: VaultId) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::PendingWaterfall(vault_id));
+}
+
+pub fn get_guardians(env: &Env, vault_id: VaultId) -> soroban_sdk::Vec<Address> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Guardians(vault_id))
+        .unwrap_or(soroban_sdk::Vec::new(env))
+}
+
+pub fn set_guardians(env: &Env, vault_id: VaultId, guardians: &soroban_sdk::Vec<Address>) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Guardians(vault_id), guardians);
+}
+
+pub fn get_pending_guardians(env: &Env, vault_id: VaultId) -> Option<PendingGuardians> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::PendingGuardians(vault_id))
+}
+
+pub fn set_pending_guardians(env: &Env, vault_id: VaultId, pending: &PendingGuardians) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::PendingGuardians(vault_id), pending);
+}
+
+pub fn remove_pending_guardians(env: &Env, vault_id: VaultId) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::PendingGuardians(vault_id));
+}
+
+pub fn get_triggered_at(env: &Env, vault_id: VaultId) -> Option<u64> {
+    env.storage().persistent().get(&DataKey::TriggeredAt(vault_id))
+}
+
+pub fn set_triggered_at(env: &Env, vault_id: VaultId, timestamp: u64) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::TriggeredAt(vault_id), &timestamp);
+}
+
+pub fn get_vault_tokens(env: &Env, vault_id: VaultId) -> soroban_sdk::Vec<Address> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::VaultTokens(vault_id))
+        .unwrap_or(soroban_sdk::Vec::new(env))
+}
+
+pub fn set_vault_tokens(env: &Env, vault_id: VaultId, tokens: &soroban_sdk::Vec<Address>) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::VaultTokens(vault_id), tokens);
+}
+
+pub fn get_balance(env: &Env, vault_id: VaultId, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Balance(vault_id, token.clone()))
+        .unwrap_or(0)
+}
+
+pub fn increase_balance(env: &Env, vault_id: VaultId, token: &Address, amount: i128) {
+    let balance = get_balance(env, vault_id, token);
+    env.storage()
+        .persistent()
+        .set(&DataKey::Balance(vault_id, token.clone()), &(balance + amount));
+    set_total_balance(env, token, get_total_balance(env, token) + amount);
+}
+
+pub fn decrease_balance(env: &Env, vault_id: VaultId, token: &Address, amount: i128) {
+    let balance = get_balance(env, vault_id, token);
+    env.storage()
+        .persistent()
+        .set(&DataKey::Balance(vault_id, token.clone()), &(balance - amount));
+    set_total_balance(env, token, get_total_balance(env, token) - amount);
+}
+
+/// Sum of every vault's balance of `token`.
+pub fn get_total_balance(env: &Env, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::TotalBalance(token.clone()))
+        .unwrap_or(0)
+}
+
+fn set_total_balance(env: &Env, token: &Address, amount: i128) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::TotalBalance(token.clone()), &amount);
+}
+
+/// Sum of every vault's principal of `token` held by strategies.
+pub fn get_total_invested(env: &Env, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::TotalInvested(token.clone()))
+        .unwrap_or(0)
+}
+
+pub fn get_bequests(env: &Env, vault_id: VaultId) -> soroban_sdk::Vec<Bequest> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Bequests(vault_id))
+        .unwrap_or(soroban_sdk::Vec::new(env))
+}
+
+pub fn set_bequests(env: &Env, vault_id: VaultId, bequests: &soroban_sdk::Vec<Bequest>) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Bequests(vault_id), bequests);
+}
+
+pub fn is_settled(env: &Env, vault_id: VaultId) -> bool {
+    env.storage().persistent().has(&DataKey::Settled(vault_id))
+}
+
+pub fn set_settled(env: &Env, vault_id: VaultId) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Settled(vault_id), &true);
+}
+
+pub fn get_executor(env: &Env, vault_id: VaultId) -> Option<ExecutorFee> {
+    env.storage().persistent().get(&DataKey::Executor(vault_id))
+}
+
+pub fn set_executor(env: &Env, vault_id: VaultId, executor: &ExecutorFee) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Executor(vault_id), executor);
+}
+
+pub fn get_creditors(env: &Env, vault_id: VaultId) -> soroban_sdk::Vec<CreditorClaim> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Creditors(vault_id))
+        .unwrap_or(soroban_sdk::Vec::new(env))
+}
+
+pub fn set_creditors(env: &Env, vault_id: VaultId, creditors: &soroban_sdk::Vec<CreditorClaim>) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Creditors(vault_id), creditors);
+}
+
+pub fn get_deposit_config(env: &Env, vault_id: VaultId) -> DepositConfig {
+    env.storage()
+        .persistent()
+        .get(&DataKey::DepositConfig(vault_id))
+        .unwrap_or(DepositConfig {
+            open: false,
+            allowlist: soroban_sdk::Vec::new(env),
+            refund_on_close: false,
+        })
+}
+
+pub fn set_deposit_config(env: &Env, vault_id: VaultId, config: &DepositConfig) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::DepositConfig(vault_id), config);
+}
+
+pub fn get_contributors(env: &Env, vault_id: VaultId) -> soroban_sdk::Vec<Address> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Contributors(vault_id))
+        .unwrap_or(soroban_sdk::Vec::new(env))
+}
+
+pub fn set_contributors(env: &Env, vault_id: VaultId, contributors: &soroban_sdk::Vec<Address>) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Contributors(vault_id), contributors);
+}
+
+pub fn get_contribution(env: &Env, vault_id: VaultId, contributor: &Address, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Contribution(vault_id, contributor.clone(), token.clone()))
+        .unwrap_or(0)
+}
+
+pub fn set_contribution(env: &Env, vault_id: VaultId, contributor: &Address, token: &Address, amount: i128) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Contribution(vault_id, contributor.clone(), token.clone()), &amount);
+}
+
+pub fn remove_waterfall(env: &Env, vault_id: VaultId) {
+    let storage = env.storage().persistent();
+    storage.remove(&DataKey::Bequests(vault_id));
+    storage.remove(&DataKey::Executor(vault_id));
+    storage.remove(&DataKey::Creditors(vault_id));
+    storage.remove(&DataKey::PendingWaterfall(vault_id));
+}
+
+pub fn get_document_registry(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::DocumentRegistry)
+}
+
+pub fn set_document_registry(env: &Env, registry: &Address) {
+    env.storage().instance().set(&DataKey::DocumentRegistry, registry);
+}
+
+pub fn get_pending_owner(env: &Env, vault_id: VaultId) -> Option<Address> {
+    env.storage().persistent().get(&DataKey::PendingOwner(vault_id))
+}
+
+pub fn set_pending_owner(env: &Env, vault_id: VaultId, new_owner: &Address) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::PendingOwner(vault_id), new_owner);
+}
+
+pub fn remove_pending_owner(env: &Env, vault_id: VaultId) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::PendingOwner(vault_id));
+}
+
+pub fn get_withdrawal_addresses(env: &Env, vault_id: VaultId) -> soroban_sdk::Vec<WithdrawalAddress> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::WithdrawalAddresses(vault_id))
+        .unwrap_or(soroban_sdk::Vec::new(env))
+}
+
+pub fn set_withdrawal_addresses(env: &Env, vault_id: VaultId, addresses: &soroban_sdk::Vec<WithdrawalAddress>) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::WithdrawalAddresses(vault_id), addresses);
+}
+
+pub fn get_withdrawal_limit(env: &Env, vault_id: VaultId, token: &Address) -> Option<WithdrawalLimit> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::WithdrawalLimit(vault_id, token.clone()))
+}
+
+pub fn set_withdrawal_limit(env: &Env, vault_id: VaultId, token: &Address, limit: &WithdrawalLimit) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::WithdrawalLimit(vault_id, token.clone()), limit);
+}
+
+pub fn remove_withdrawal_limit(env: &Env, vault_id: VaultId, token: &Address) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::WithdrawalLimit(vault_id, token.clone()));
+}
+
+pub fn get_pending_withdrawal_limit(env: &Env, vault_id: VaultId, token: &Address) -> Option<PendingWithdrawalLimit> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::PendingWithdrawalLimit(vault_id, token.clone()))
+}
+
+pub fn set_pending_withdrawal_limit(env: &Env, vault_id: VaultId, token: &Address, pending: &PendingWithdrawalLimit) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::PendingWithdrawalLimit(vault_id, token.clone()), pending);
+}
+
+pub fn remove_pending_withdrawal_limit(env: &Env, vault_id: VaultId, token: &Address) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::PendingWithdrawalLimit(vault_id, token.clone()));
+}
+
+pub fn get_recent_withdrawals(env: &Env, vault_id: VaultId, token: &Address) -> soroban_sdk::Vec<WithdrawalRecord> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::RecentWithdrawals(vault_id, token.clone()))
+        .unwrap_or(soroban_sdk::Vec::new(env))
+}
+
+pub fn set_recent_withdrawals(
+    env: &Env,
+    vault_id: VaultId,
+    token: &Address,
+    records: &soroban_sdk::Vec<WithdrawalRecord>,
+) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::RecentWithdrawals(vault_id, token.clone()), records);
+}
+
+pub fn get_frozen_until(env: &Env, vault_id: VaultId) -> Option<u64> {
+    env.storage().persistent().get(&DataKey::FrozenUntil(vault_id))
+}
+
+pub fn set_frozen_until(env: &Env, vault_id: VaultId, until: u64) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::FrozenUntil(vault_id), &until);
+}
+
+pub fn get_dispute_config(env: &Env, vault_id: VaultId) -> Option<DisputeConfig> {
+    env.storage().persistent().get(&DataKey::DisputeConfig(vault_id))
+}
+
+pub fn set_dispute_config(env: &Env, vault_id: VaultId, config: &DisputeConfig) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::DisputeConfig(vault_id), config);
+}
+
+pub fn remove_dispute_config(env: &Env, vault_id: VaultId) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::DisputeConfig(vault_id));
+}
+
+pub fn get_disputes(env: &Env, vault_id: VaultId) -> soroban_sdk::Vec<Dispute> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Disputes(vault_id))
+        .unwrap_or(soroban_sdk::Vec::new(env))
+}
+
+pub fn set_disputes(env: &Env, vault_id: VaultId, disputes: &soroban_sdk::Vec<Dispute>) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Disputes(vault_id), disputes);
+}
+
+pub fn get_fee_config(env: &Env) -> FeeConfig {
+    env.storage()
+        .instance()
+        .get(&DataKey::FeeConfig)
+        .unwrap_or(FeeConfig { deposit_bps: 0, claim_bps: 0 })
+}
+
+pub fn set_fee_config(env: &Env, config: &FeeConfig) {
+    env.storage().instance().set(&DataKey::FeeConfig, config);
+}
+
+pub fn get_vault_fees(env: &Env, vault_id: VaultId) -> FeeConfig {
+    env.storage()
+        .persistent()
+        .get(&DataKey::VaultFees(vault_id))
+        .unwrap_or(FeeConfig { deposit_bps: 0, claim_bps: 0 })
+}
+
+pub fn set_vault_fees(env: &Env, vault_id: VaultId, fees: &FeeConfig) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::VaultFees(vault_id), fees);
+}
+
+pub fn get_treasury(env: &Env, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Treasury(token.clone()))
+        .unwrap_or(0)
+}
+
+pub fn set_treasury(env: &Env, token: &Address, amount: i128) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Treasury(token.clone()), &amount);
+}
+
+pub fn get_allowances(env: &Env, vault_id: VaultId) -> soroban_sdk::Vec<Allowance> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Allowances(vault_id))
+        .unwrap_or(soroban_sdk::Vec::new(env))
+}
+
+pub fn set_allowances(env: &Env, vault_id: VaultId, allowances: &soroban_sdk::Vec<Allowance>) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Allowances(vault_id), allowances);
+}
+
+pub fn remove_allowances(env: &Env, vault_id: VaultId) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::Allowances(vault_id));
+}
+
+pub fn get_joint_owners(env: &Env, vault_id: VaultId) -> soroban_sdk::Vec<Address> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::JointOwners(vault_id))
+        .unwrap_or(soroban_sdk::Vec::new(env))
+}
+
+pub fn set_joint_owners(env: &Env, vault_id: VaultId, owners: &soroban_sdk::Vec<Address>) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::JointOwners(vault_id), owners);
+}
+
+pub fn remove_joint_owners(env: &Env, vault_id: VaultId) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::JointOwners(vault_id));
+}
+
+pub fn get_owner_status(env: &Env, vault_id: VaultId, owner: &Address) -> VaultStatus {
+    env.storage()
+        .persistent()
+        .get(&DataKey::OwnerStatus(vault_id, owner.clone()))
+        .unwrap_or(VaultStatus::Active)
+}
+
+pub fn set_owner_status(env: &Env, vault_id: VaultId, owner: &Address, status: &VaultStatus) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::OwnerStatus(vault_id, owner.clone()), status);
+}
+
+pub fn remove_owner_status(env: &Env, vault_id: VaultId, owner: &Address) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::OwnerStatus(vault_id, owner.clone()));
+}
+
+pub fn get_strategy(env: &Env, vault_id: VaultId) -> Option<Address> {
+    env.storage().persistent().get(&DataKey::Strategy(vault_id))
+}
+
+pub fn set_strategy(env: &Env, vault_id: VaultId, strategy: &Address) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Strategy(vault_id), strategy);
+}
+
+pub fn remove_strategy(env: &Env, vault_id: VaultId) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::Strategy(vault_id));
+}
+
+pub fn get_invested(env: &Env, vault_id: VaultId, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Invested(vault_id, token.clone()))
+        .unwrap_or(0)
+}
+
+pub fn set_invested(env: &Env, vault_id: VaultId, token: &Address, amount: i128) {
+    let total = get_total_invested(env, token) - get_invested(env, vault_id, token) + amount;
+    env.storage()
+        .persistent()
+        .set(&DataKey::TotalInvested(token.clone()), &total);
+
+    let key = DataKey::Invested(vault_id, token.clone());
+    if amount == 0 {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, &amount);
+    }
+}
+
+pub fn get_owner_vaults(env: &Env, owner: &Address) -> soroban_sdk::Vec<VaultId> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::OwnerVaults(owner.clone()))
+        .unwrap_or(soroban_sdk::Vec::new(env))
+}
+
+pub fn add_owner_vault(env: &Env, owner: &Address, vault_id: VaultId) {
+    let mut vaults = get_owner_vaults(env, owner);
+    if !vaults.contains(vault_id) {
+        vaults.push_back(vault_id);
+        env.storage()
+            .persistent()
+            .set(&DataKey::OwnerVaults(owner.clone()), &vaults);
+    }
+}
+
+pub fn remove_owner_vault(env: &Env, owner: &Address, vault_id: VaultId) {
+    let mut vaults = get_owner_vaults(env, owner);
+    if let Some(index) = vaults.first_index_of(vault_id) {
+        vaults.remove(index);
+        let key = DataKey::OwnerVaults(owner.clone());
+        if vaults.is_empty() {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &vaults);
+        }
+    }
+}
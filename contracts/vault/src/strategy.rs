@@ -0,0 +1,12 @@
+use soroban_sdk::{contractclient, Address, Env};
+
+/// Interface a yield strategy must expose to hold idle vault funds.
+/// Positions are keyed by depositor and an opaque account id so several
+/// vaults on one contract can share a strategy. The depositor transfers the
+/// tokens before calling `deposit`; `withdraw` pays back to the depositor.
+#[contractclient(name = "StrategyClient")]
+pub trait StrategyInterface {
+    fn deposit(env: Env, depositor: Address, account: u64, token: Address, amount: i128);
+    fn withdraw(env: Env, depositor: Address, account: u64, token: Address, amount: i128);
+    fn total_value(env: Env, depositor: Address, account: u64, token: Address) -> i128;
+}
@@ -2,10 +2,15 @@
 
 use super::*;
 use contract::{VaultContract, VaultContractClient};
-use soroban_sdk::testutils::Address as _;
+use mock_strategy::{MockStrategy, MockStrategyClient};
+use soroban_sdk::testutils::{Address as _, Ledger};
 use soroban_sdk::token::{StellarAssetClient, TokenClient};
-use soroban_sdk::{Address, Env, Vec};
-use types::{Beneficiary, VaultStatus};
+use soroban_sdk::{Address, BytesN, Env, Vec};
+use types::{
+    Bequest, BequestKind, Beneficiary, BeneficiaryCommitment, CreditorClaim, DepositConfig, Payout, TokenBalance,
+    VaultStatus, WithdrawalLimit,
+};
+use types::{BalanceReport, DisputeConfig, DisputeRuling, DisputeStatus, FeeConfig};
 
 // ---------------------------------------------------------------------------
 // Helpers
@@ -28,6 +33,18 @@ fn create_token<'a>(env: &Env, admin: &Address) -> (Address, TokenClient<'a>, St
     (contract_id.address(), client, admin_client)
 }
 
+fn single_beneficiary(env: &Env, address: &Address) -> Vec<Beneficiary> {
+    let mut beneficiaries = Vec::new(env);
+    beneficiaries.push_back(Beneficiary { address: address.clone(), percentage: 10_000, claimed: false });
+    beneficiaries
+}
+
+fn assert_reconciled(client: &VaultContractClient, token: &Address) {
+    let report: BalanceReport = client.sync_balance(token);
+    assert_eq!(report.surplus, 0);
+    assert_eq!(report.actual, report.tracked);
+}
+
 // ---------------------------------------------------------------------------
 // 1. test_initialize
 // ---------------------------------------------------------------------------
@@ -78,7 +95,8 @@ fn test_create_vault() {
     assert_eq!(vault.owner, owner);
     assert_eq!(vault.token, token);
     assert_eq!(vault.status, VaultStatus::Active);
-    assert_eq!(vault.balance, 0);
+    assert_eq!(client.get_balance(&vault_id, &token), 0);
+    assert_eq!(client.get_balances(&vault_id).len(), 1);
 }
 
 // ---------------------------------------------------------------------------
@@ -127,14 +145,10 @@ fn test_deposit() {
 
     // Deposit into vault
     let deposit_amount: i128 = 5_000;
-    client.deposit(&vault_id, &owner, &deposit_amount);
+    client.deposit(&vault_id, &token_addr, &owner, &deposit_amount);
 
     // Verify vault balance updated
-    let vault = client.get_vault(&vault_id);
-    assert_eq!(vault.balance, deposit_amount);
-
-    // Verify the balance from convenience getter
-    let balance = client.get_balance(&vault_id);
+    let balance = client.get_balance(&vault_id, &token_addr);
     assert_eq!(balance, deposit_amount);
 }
 
@@ -152,7 +166,7 @@ fn test_deposit_zero_amount() {
     let vault_id = client.create_vault(&owner, &token);
 
     // Depositing zero should fail with ZeroAmount
-    let result = client.try_deposit(&vault_id, &owner, &0);
+    let result = client.try_deposit(&vault_id, &token, &owner, &0);
     assert!(result.is_err());
 }
 
@@ -175,7 +189,7 @@ fn test_deposit_not_owner() {
     token_admin_client.mint(&not_owner, &10_000);
 
     // Deposit from not_owner should fail with NotAuthorized
-    let result = client.try_deposit(&vault_id, &not_owner, &1_000);
+    let result = client.try_deposit(&vault_id, &token_addr, &not_owner, &1_000);
     assert!(result.is_err());
 }
 
@@ -195,14 +209,13 @@ fn test_withdraw_active() {
 
     // Mint and deposit
     token_admin_client.mint(&owner, &10_000);
-    client.deposit(&vault_id, &owner, &10_000);
+    client.deposit(&vault_id, &token_addr, &owner, &10_000);
 
     // Withdraw partial amount
-    client.withdraw(&vault_id, &owner, &3_000);
+    client.withdraw(&vault_id, &token_addr, &owner, &3_000);
 
     // Vault balance should be reduced
-    let vault = client.get_vault(&vault_id);
-    assert_eq!(vault.balance, 7_000);
+    assert_eq!(client.get_balance(&vault_id, &token_addr), 7_000);
 
     // Owner token balance should reflect the withdrawal
     assert_eq!(token_client.balance(&owner), 3_000);
@@ -224,13 +237,13 @@ fn test_withdraw_not_active() {
 
     // Mint and deposit
     token_admin_client.mint(&owner, &10_000);
-    client.deposit(&vault_id, &owner, &10_000);
+    client.deposit(&vault_id, &token_addr, &owner, &10_000);
 
     // Transition vault to Alert (not Active)
     client.transition_status(&vault_id, &admin, &VaultStatus::Alert);
 
     // Withdrawal should fail with InvalidStatus
-    let result = client.try_withdraw(&vault_id, &owner, &1_000);
+    let result = client.try_withdraw(&vault_id, &token_addr, &owner, &1_000);
     assert!(result.is_err());
 }
 
@@ -368,3 +381,1369 @@ fn test_transition_status_unauthorized() {
     let result = client.try_transition_status(&vault_id, &random_user, &VaultStatus::Alert);
     assert!(result.is_err());
 }
+
+// ---------------------------------------------------------------------------
+// 15. test_claim_pays_percentage_shares
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_claim_pays_percentage_shares() {
+    let (env, admin, client) = setup_env();
+
+    let owner = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let (token_addr, token_client, token_admin_client) = create_token(&env, &token_admin);
+
+    let vault_id = client.create_vault(&owner, &token_addr);
+    token_admin_client.mint(&owner, &10_000);
+    client.deposit(&vault_id, &token_addr, &owner, &10_000);
+
+    let heir1 = Address::generate(&env);
+    let heir2 = Address::generate(&env);
+    let mut beneficiaries = Vec::new(&env);
+    beneficiaries.push_back(Beneficiary { address: heir1.clone(), percentage: 6_000, claimed: false });
+    beneficiaries.push_back(Beneficiary { address: heir2.clone(), percentage: 4_000, claimed: false });
+    client.set_beneficiaries(&vault_id, &beneficiaries);
+
+    // Claims are rejected until the vault is Triggered
+    assert!(client.try_claim(&vault_id, &heir1).is_err());
+
+    client.transition_status(&vault_id, &admin, &VaultStatus::Triggered);
+
+    assert_eq!(client.claim(&vault_id, &heir1).get(0).unwrap().amount, 6_000);
+    assert_eq!(token_client.balance(&heir1), 6_000);
+
+    // Double claim => AlreadyClaimed
+    assert!(client.try_claim(&vault_id, &heir1).is_err());
+
+    // Outsider => NotABeneficiary
+    let outsider = Address::generate(&env);
+    assert!(client.try_claim(&vault_id, &outsider).is_err());
+
+    assert_eq!(client.claim(&vault_id, &heir2).get(0).unwrap().amount, 4_000);
+    assert_eq!(client.get_balance(&vault_id, &token_addr), 0);
+    assert_eq!(client.get_status(&vault_id), VaultStatus::Distributed);
+}
+
+// ---------------------------------------------------------------------------
+// 16. test_commitments_hide_beneficiaries
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_commitments_hide_beneficiaries() {
+    let (env, admin, client) = setup_env();
+
+    let owner = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let (token_addr, token_client, token_admin_client) = create_token(&env, &token_admin);
+
+    let vault_id = client.create_vault(&owner, &token_addr);
+    token_admin_client.mint(&owner, &10_000);
+    client.deposit(&vault_id, &token_addr, &owner, &10_000);
+
+    let heir1 = Address::generate(&env);
+    let heir2 = Address::generate(&env);
+    let salt1 = BytesN::from_array(&env, &[7u8; 32]);
+    let salt2 = BytesN::from_array(&env, &[9u8; 32]);
+
+    let mut commitments = Vec::new(&env);
+    commitments.push_back(BeneficiaryCommitment {
+        commitment: client.compute_commitment(&heir1, &salt1),
+        percentage: 7_500,
+        claimed: false,
+    });
+    commitments.push_back(BeneficiaryCommitment {
+        commitment: client.compute_commitment(&heir2, &salt2),
+        percentage: 2_500,
+        claimed: false,
+    });
+    client.set_beneficiary_commitments(&vault_id, &commitments);
+
+    // Public views expose only commitments
+    assert_eq!(client.get_beneficiaries(&vault_id).len(), 0);
+    assert_eq!(client.get_beneficiary_commitments(&vault_id).len(), 2);
+
+    client.transition_status(&vault_id, &admin, &VaultStatus::Triggered);
+
+    // Wrong salt => InvalidCommitment
+    let result = client.try_claim_committed(&vault_id, &heir1, &salt2);
+    assert!(result.is_err());
+
+    assert_eq!(client.claim_committed(&vault_id, &heir1, &salt1).get(0).unwrap().amount, 7_500);
+    assert_eq!(token_client.balance(&heir1), 7_500);
+    assert!(client.try_claim_committed(&vault_id, &heir1, &salt1).is_err());
+
+    assert_eq!(client.claim_committed(&vault_id, &heir2, &salt2).get(0).unwrap().amount, 2_500);
+    assert_eq!(client.get_status(&vault_id), VaultStatus::Distributed);
+}
+
+// ---------------------------------------------------------------------------
+// 17. test_commitments_invalid_sum
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_commitments_invalid_sum() {
+    let (env, _admin, client) = setup_env();
+
+    let owner = Address::generate(&env);
+    let token = Address::generate(&env);
+    let vault_id = client.create_vault(&owner, &token);
+
+    let mut commitments = Vec::new(&env);
+    commitments.push_back(BeneficiaryCommitment {
+        commitment: BytesN::from_array(&env, &[1u8; 32]),
+        percentage: 5_000,
+        claimed: false,
+    });
+
+    // Sum = 5000 => PercentageSumInvalid
+    let result = client.try_set_beneficiary_commitments(&vault_id, &commitments);
+    assert!(result.is_err());
+}
+
+// ---------------------------------------------------------------------------
+// 18. test_beneficiary_change_timelocked
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_beneficiary_change_timelocked() {
+    let (env, _admin, client) = setup_env();
+
+    let owner = Address::generate(&env);
+    let token = Address::generate(&env);
+    let vault_id = client.create_vault(&owner, &token);
+
+    let heir = Address::generate(&env);
+    let attacker = Address::generate(&env);
+
+    // First configuration applies immediately
+    client.set_beneficiaries(&vault_id, &single_beneficiary(&env, &heir));
+    assert_eq!(client.get_beneficiaries(&vault_id).get(0).unwrap().address, heir);
+
+    // Replacing it is queued
+    client.set_beneficiaries(&vault_id, &single_beneficiary(&env, &attacker));
+    assert_eq!(client.get_beneficiaries(&vault_id).get(0).unwrap().address, heir);
+    let pending = client.get_pending_beneficiaries(&vault_id).unwrap();
+    assert_eq!(pending.executable_at, client.get_beneficiary_delay());
+
+    // Too early => TimelockActive
+    assert!(client.try_execute_pending_beneficiaries(&vault_id).is_err());
+
+    env.ledger().set_timestamp(pending.executable_at);
+    client.execute_pending_beneficiaries(&vault_id);
+    assert_eq!(client.get_beneficiaries(&vault_id).get(0).unwrap().address, attacker);
+    assert!(client.get_pending_beneficiaries(&vault_id).is_none());
+}
+
+// ---------------------------------------------------------------------------
+// 19. test_guardian_cancels_pending_change
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_guardian_cancels_pending_change() {
+    let (env, _admin, client) = setup_env();
+
+    let owner = Address::generate(&env);
+    let token = Address::generate(&env);
+    let vault_id = client.create_vault(&owner, &token);
+
+    let guardian = Address::generate(&env);
+    let mut guardians = Vec::new(&env);
+    guardians.push_back(guardian.clone());
+    client.set_guardians(&vault_id, &guardians);
+
+    let heir = Address::generate(&env);
+    let attacker = Address::generate(&env);
+    client.set_beneficiaries(&vault_id, &single_beneficiary(&env, &heir));
+    client.set_beneficiaries(&vault_id, &single_beneficiary(&env, &attacker));
+
+    // Random caller => NotAuthorized
+    let stranger = Address::generate(&env);
+    assert!(client.try_cancel_pending_beneficiaries(&vault_id, &stranger).is_err());
+
+    client.cancel_pending_beneficiaries(&vault_id, &guardian);
+    assert!(client.get_pending_beneficiaries(&vault_id).is_none());
+
+    env.ledger().set_timestamp(client.get_beneficiary_delay());
+    assert!(client.try_execute_pending_beneficiaries(&vault_id).is_err());
+    assert_eq!(client.get_beneficiaries(&vault_id).get(0).unwrap().address, heir);
+}
+
+// ---------------------------------------------------------------------------
+// 20. test_pending_change_dropped_when_leaving_active
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_pending_change_dropped_when_leaving_active() {
+    let (env, admin, client) = setup_env();
+
+    let owner = Address::generate(&env);
+    let token = Address::generate(&env);
+    let vault_id = client.create_vault(&owner, &token);
+
+    let heir = Address::generate(&env);
+    let attacker = Address::generate(&env);
+    client.set_beneficiaries(&vault_id, &single_beneficiary(&env, &heir));
+    client.set_beneficiaries(&vault_id, &single_beneficiary(&env, &attacker));
+
+    // Leaving Active discards the queued change, even if the vault recovers
+    client.transition_status(&vault_id, &admin, &VaultStatus::Alert);
+    client.transition_status(&vault_id, &admin, &VaultStatus::Active);
+    assert!(client.get_pending_beneficiaries(&vault_id).is_none());
+
+    env.ledger().set_timestamp(client.get_beneficiary_delay());
+    assert!(client.try_execute_pending_beneficiaries(&vault_id).is_err());
+    assert_eq!(client.get_beneficiaries(&vault_id).get(0).unwrap().address, heir);
+}
+
+// ---------------------------------------------------------------------------
+// 21. test_multi_asset_vault
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_multi_asset_vault() {
+    let (env, admin, client) = setup_env();
+
+    let owner = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let (xlm, xlm_client, xlm_admin) = create_token(&env, &token_admin);
+    let (usdc, usdc_client, usdc_admin) = create_token(&env, &token_admin);
+
+    let vault_id = client.create_vault(&owner, &xlm);
+    xlm_admin.mint(&owner, &10_000);
+    usdc_admin.mint(&owner, &2_000);
+    client.deposit(&vault_id, &xlm, &owner, &10_000);
+    client.deposit(&vault_id, &usdc, &owner, &2_000);
+
+    // Withdrawals are per token
+    client.withdraw(&vault_id, &usdc, &owner, &1_000);
+    assert_eq!(client.get_balance(&vault_id, &usdc), 1_000);
+    assert!(client.try_withdraw(&vault_id, &usdc, &owner, &5_000).is_err());
+
+    let balances = client.get_balances(&vault_id);
+    assert_eq!(balances.len(), 2);
+    assert_eq!(balances.get(0).unwrap(), TokenBalance { token: xlm.clone(), amount: 10_000 });
+    assert_eq!(balances.get(1).unwrap(), TokenBalance { token: usdc.clone(), amount: 1_000 });
+
+    let heir1 = Address::generate(&env);
+    let heir2 = Address::generate(&env);
+    let mut beneficiaries = Vec::new(&env);
+    beneficiaries.push_back(Beneficiary { address: heir1.clone(), percentage: 5_000, claimed: false });
+    beneficiaries.push_back(Beneficiary { address: heir2.clone(), percentage: 5_000, claimed: false });
+    client.set_beneficiaries(&vault_id, &beneficiaries);
+    client.transition_status(&vault_id, &admin, &VaultStatus::Triggered);
+
+    // Each claim pays the percentage of every token
+    let paid = client.claim(&vault_id, &heir1);
+    assert_eq!(paid.len(), 2);
+    assert_eq!(xlm_client.balance(&heir1), 5_000);
+    assert_eq!(usdc_client.balance(&heir1), 500);
+
+    client.claim(&vault_id, &heir2);
+    assert_eq!(xlm_client.balance(&heir2), 5_000);
+    assert_eq!(usdc_client.balance(&heir2), 500);
+    assert_eq!(client.get_balance(&vault_id, &xlm), 0);
+    assert_eq!(client.get_balance(&vault_id, &usdc), 0);
+}
+
+// ---------------------------------------------------------------------------
+// 22. test_bequests_paid_before_residue
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_bequests_paid_before_residue() {
+    let (env, admin, client) = setup_env();
+
+    let owner = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let (xlm, xlm_client, xlm_admin) = create_token(&env, &token_admin);
+    let (eurc, eurc_client, eurc_admin) = create_token(&env, &token_admin);
+
+    let vault_id = client.create_vault(&owner, &xlm);
+    xlm_admin.mint(&owner, &10_000);
+    eurc_admin.mint(&owner, &3_000);
+    client.deposit(&vault_id, &xlm, &owner, &10_000);
+    client.deposit(&vault_id, &eurc, &owner, &3_000);
+
+    // All EURC to the sister, 2_000 XLM to a friend, the rest of the XLM 50/50
+    let sister = Address::generate(&env);
+    let friend = Address::generate(&env);
+    let mut bequests = Vec::new(&env);
+    bequests.push_back(Bequest { recipient: sister.clone(), token: eurc.clone(), kind: BequestKind::WholeToken, amount: 0, paid: false });
+    bequests.push_back(Bequest { recipient: friend.clone(), token: xlm.clone(), kind: BequestKind::Fixed, amount: 2_000, paid: false });
+    client.set_bequests(&vault_id, &bequests);
+
+    let heir1 = Address::generate(&env);
+    let heir2 = Address::generate(&env);
+    let mut beneficiaries = Vec::new(&env);
+    beneficiaries.push_back(Beneficiary { address: heir1.clone(), percentage: 5_000, claimed: false });
+    beneficiaries.push_back(Beneficiary { address: heir2.clone(), percentage: 5_000, claimed: false });
+    client.set_beneficiaries(&vault_id, &beneficiaries);
+
+    // Bequests cannot be claimed before the trigger
+    assert!(client.try_claim_bequests(&vault_id, &sister).is_err());
+    client.transition_status(&vault_id, &admin, &VaultStatus::Triggered);
+
+    // Residue claims never eat into unpaid bequests
+    client.claim(&vault_id, &heir1);
+    client.claim(&vault_id, &heir2);
+    assert_eq!(xlm_client.balance(&heir1), 4_000);
+    assert_eq!(xlm_client.balance(&heir2), 4_000);
+    assert_eq!(eurc_client.balance(&heir1), 0);
+    assert_eq!(client.get_status(&vault_id), VaultStatus::Triggered);
+
+    client.claim_bequests(&vault_id, &sister);
+    assert_eq!(eurc_client.balance(&sister), 3_000);
+    assert!(client.try_claim_bequests(&vault_id, &sister).is_err());
+
+    client.claim_bequests(&vault_id, &friend);
+    assert_eq!(xlm_client.balance(&friend), 2_000);
+    assert_eq!(client.get_status(&vault_id), VaultStatus::Distributed);
+}
+
+// ---------------------------------------------------------------------------
+// 23. test_bequests_cannot_over_allocate
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_bequests_cannot_over_allocate() {
+    let (env, _admin, client) = setup_env();
+
+    let owner = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let (xlm, _xlm_client, xlm_admin) = create_token(&env, &token_admin);
+
+    let vault_id = client.create_vault(&owner, &xlm);
+    xlm_admin.mint(&owner, &1_000);
+    client.deposit(&vault_id, &xlm, &owner, &1_000);
+
+    let a = Address::generate(&env);
+    let b = Address::generate(&env);
+    let fixed = |to: &Address, amount: i128| Bequest {
+        recipient: to.clone(),
+        token: xlm.clone(),
+        kind: BequestKind::Fixed,
+        amount,
+        paid: false,
+    };
+    let whole = |to: &Address| Bequest {
+        recipient: to.clone(),
+        token: xlm.clone(),
+        kind: BequestKind::WholeToken,
+        amount: 0,
+        paid: false,
+    };
+
+    // Fixed amounts exceeding the balance
+    let mut bequests = Vec::new(&env);
+    bequests.push_back(fixed(&a, 600));
+    bequests.push_back(fixed(&b, 500));
+    let result = client.try_set_bequests(&vault_id, &bequests);
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::OverAllocated);
+
+    // A whole-token bequest combined with a fixed one of the same token
+    let mut bequests = Vec::new(&env);
+    bequests.push_back(fixed(&a, 100));
+    bequests.push_back(whole(&b));
+    let result = client.try_set_bequests(&vault_id, &bequests);
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::OverAllocated);
+
+    // The same token left whole twice
+    let mut bequests = Vec::new(&env);
+    bequests.push_back(whole(&a));
+    bequests.push_back(whole(&b));
+    let result = client.try_set_bequests(&vault_id, &bequests);
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::InvalidBequests);
+
+    let mut bequests = Vec::new(&env);
+    bequests.push_back(fixed(&a, 600));
+    bequests.push_back(fixed(&b, 400));
+    client.set_bequests(&vault_id, &bequests);
+    assert_eq!(client.get_bequests(&vault_id).len(), 2);
+}
+
+// ---------------------------------------------------------------------------
+// 24. test_waterfall_tiers
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_waterfall_tiers() {
+    let (env, admin, client) = setup_env();
+
+    let owner = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let (xlm, xlm_client, xlm_admin) = create_token(&env, &token_admin);
+
+    let vault_id = client.create_vault(&owner, &xlm);
+    xlm_admin.mint(&owner, &10_000);
+    client.deposit(&vault_id, &xlm, &owner, &10_000);
+
+    let executor = Address::generate(&env);
+    let bank = Address::generate(&env);
+    let funeral = Address::generate(&env);
+    let heir1 = Address::generate(&env);
+    let heir2 = Address::generate(&env);
+
+    // Executor fee is capped
+    let result = client.try_set_executor(&vault_id, &executor, &5_000);
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::FeeTooHigh);
+    client.set_executor(&vault_id, &executor, &200);
+
+    let mut creditors = Vec::new(&env);
+    creditors.push_back(CreditorClaim { creditor: bank.clone(), token: xlm.clone(), amount: 3_000, paid: false });
+    creditors.push_back(CreditorClaim { creditor: funeral.clone(), token: xlm.clone(), amount: 800, paid: false });
+
+    // With the executor named, further waterfall changes wait out the delay
+    client.set_creditors(&vault_id, &creditors);
+    assert_eq!(client.get_creditors(&vault_id).len(), 0);
+    env.ledger().set_timestamp(7 * 24 * 60 * 60);
+    client.execute_pending_waterfall(&vault_id);
+    assert_eq!(client.get_creditors(&vault_id).len(), 2);
+
+    let mut beneficiaries = Vec::new(&env);
+    beneficiaries.push_back(Beneficiary { address: heir1.clone(), percentage: 5_000, claimed: false });
+    beneficiaries.push_back(Beneficiary { address: heir2.clone(), percentage: 5_000, claimed: false });
+    client.set_beneficiaries(&vault_id, &beneficiaries);
+
+    // 200 to the executor, 3_800 in debts, 6_000 residue split 50/50
+    let preview = client.preview_distribution(&vault_id);
+    assert_eq!(preview.len(), 5);
+    assert_eq!(preview.get(0).unwrap(), Payout { recipient: executor.clone(), token: xlm.clone(), amount: 200 });
+    assert_eq!(preview.get(1).unwrap(), Payout { recipient: bank.clone(), token: xlm.clone(), amount: 3_000 });
+    assert_eq!(preview.get(2).unwrap(), Payout { recipient: funeral.clone(), token: xlm.clone(), amount: 800 });
+    assert_eq!(preview.get(3).unwrap(), Payout { recipient: heir1.clone(), token: xlm.clone(), amount: 3_000 });
+    assert_eq!(preview.get(4).unwrap(), Payout { recipient: heir2.clone(), token: xlm.clone(), amount: 3_000 });
+
+    client.transition_status(&vault_id, &admin, &VaultStatus::Triggered);
+
+    // Each tier waits for the one before it
+    let result = client.try_claim_debt(&vault_id, &bank);
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::PriorTierPending);
+    client.claim_executor_fee(&vault_id);
+    assert_eq!(xlm_client.balance(&executor), 200);
+
+    let result = client.try_claim(&vault_id, &heir1);
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::PriorTierPending);
+    client.claim_debt(&vault_id, &bank);
+    assert_eq!(xlm_client.balance(&bank), 3_000);
+    assert!(client.try_claim(&vault_id, &heir1).is_err());
+
+    // A released debt flows down to the heirs
+    client.release_debt(&vault_id, &funeral);
+    client.claim(&vault_id, &heir1);
+    client.claim(&vault_id, &heir2);
+    assert_eq!(xlm_client.balance(&funeral), 0);
+    assert_eq!(xlm_client.balance(&heir1), 3_400);
+    assert_eq!(xlm_client.balance(&heir2), 3_400);
+    assert_eq!(client.get_status(&vault_id), VaultStatus::Distributed);
+    assert_eq!(client.preview_distribution(&vault_id).len(), 0);
+}
+
+// ---------------------------------------------------------------------------
+// 25. test_third_party_deposits
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_third_party_deposits() {
+    let (env, _admin, client) = setup_env();
+
+    let owner = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let (token, _token_client, token_admin_client) = create_token(&env, &token_admin);
+    let vault_id = client.create_vault(&owner, &token);
+
+    let grandma = Address::generate(&env);
+    let employer = Address::generate(&env);
+    token_admin_client.mint(&owner, &1_000);
+    token_admin_client.mint(&grandma, &1_000);
+    token_admin_client.mint(&employer, &1_000);
+
+    // Owner only by default
+    let result = client.try_deposit(&vault_id, &token, &grandma, &100);
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::NotAuthorized);
+
+    let mut allowlist = Vec::new(&env);
+    allowlist.push_back(grandma.clone());
+    client.set_deposit_config(&vault_id, &DepositConfig { open: false, allowlist: allowlist.clone(), refund_on_close: false });
+    client.deposit(&vault_id, &token, &owner, &500);
+    client.deposit(&vault_id, &token, &grandma, &100);
+    client.deposit(&vault_id, &token, &grandma, &150);
+    assert!(client.try_deposit(&vault_id, &token, &employer, &100).is_err());
+
+    // Open mode accepts anyone
+    client.set_deposit_config(&vault_id, &DepositConfig { open: true, allowlist, refund_on_close: true });
+    client.deposit(&vault_id, &token, &employer, &300);
+
+    // Only the owner can add a new token to the vault
+    let (junk, _, junk_admin_client) = create_token(&env, &token_admin);
+    junk_admin_client.mint(&employer, &1_000);
+    let result = client.try_deposit(&vault_id, &junk, &employer, &100);
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::NotAuthorized);
+    assert_eq!(client.get_balances(&vault_id).len(), 1);
+
+    assert_eq!(client.get_balance(&vault_id, &token), 1_050);
+    assert_eq!(client.get_contribution(&vault_id, &grandma, &token), 250);
+    assert_eq!(client.get_contribution(&vault_id, &employer, &token), 300);
+    assert_eq!(client.get_contribution(&vault_id, &owner, &token), 500);
+    assert_eq!(client.get_contributors(&vault_id).len(), 3);
+
+    // Strangers filling the contributor list cannot lock out the family
+    for _ in 3..50 {
+        let stranger = Address::generate(&env);
+        token_admin_client.mint(&stranger, &1);
+        client.deposit(&vault_id, &token, &stranger, &1);
+    }
+    let stranger = Address::generate(&env);
+    token_admin_client.mint(&stranger, &1);
+    let result = client.try_deposit(&vault_id, &token, &stranger, &1);
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::TooManyContributors);
+
+    let aunt = Address::generate(&env);
+    token_admin_client.mint(&aunt, &100);
+    let mut allowlist = Vec::new(&env);
+    allowlist.push_back(grandma.clone());
+    allowlist.push_back(aunt.clone());
+    client.set_deposit_config(&vault_id, &DepositConfig { open: true, allowlist, refund_on_close: true });
+    client.deposit(&vault_id, &token, &aunt, &100);
+    assert_eq!(client.get_contributors(&vault_id).len(), 51);
+}
+
+// ---------------------------------------------------------------------------
+// 26. test_close_vault_refunds_owner
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_close_vault_refunds_owner() {
+    let (env, admin, client) = setup_env();
+
+    let owner = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let (xlm, xlm_client, xlm_admin) = create_token(&env, &token_admin);
+    let (usdc, usdc_client, usdc_admin) = create_token(&env, &token_admin);
+
+    let vault_id = client.create_vault(&owner, &xlm);
+    xlm_admin.mint(&owner, &1_000);
+    usdc_admin.mint(&owner, &500);
+    client.deposit(&vault_id, &xlm, &owner, &1_000);
+    client.deposit(&vault_id, &usdc, &owner, &500);
+
+    let heir = Address::generate(&env);
+    client.set_beneficiaries(&vault_id, &single_beneficiary(&env, &heir));
+    client.link_proof_of_life(&vault_id, &Address::generate(&env));
+
+    client.close_vault(&vault_id);
+    assert_eq!(xlm_client.balance(&owner), 1_000);
+    assert_eq!(usdc_client.balance(&owner), 500);
+    assert_eq!(client.get_balance(&vault_id, &xlm), 0);
+    assert_eq!(client.get_status(&vault_id), VaultStatus::Closed);
+    assert_eq!(client.get_beneficiaries(&vault_id).len(), 0);
+
+    // Closed is terminal
+    assert!(client.try_deposit(&vault_id, &xlm, &owner, &100).is_err());
+    assert!(client.try_close_vault(&vault_id).is_err());
+    let result = client.try_transition_status(&vault_id, &admin, &VaultStatus::Active);
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::InvalidStatus);
+}
+
+// ---------------------------------------------------------------------------
+// 27. test_close_vault_refunds_contributors_first
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_close_vault_refunds_contributors_first() {
+    let (env, admin, client) = setup_env();
+
+    let owner = Address::generate(&env);
+    let uncle = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let (token, token_client, token_admin_client) = create_token(&env, &token_admin);
+
+    let vault_id = client.create_vault(&owner, &token);
+    client.set_deposit_config(&vault_id, &DepositConfig { open: true, allowlist: Vec::new(&env), refund_on_close: true });
+    token_admin_client.mint(&owner, &3_000);
+    token_admin_client.mint(&uncle, &1_000);
+    client.deposit(&vault_id, &token, &owner, &3_000);
+    client.deposit(&vault_id, &token, &uncle, &1_000);
+
+    // Only Active vaults can be closed
+    client.transition_status(&vault_id, &admin, &VaultStatus::Alert);
+    assert!(client.try_close_vault(&vault_id).is_err());
+    client.transition_status(&vault_id, &admin, &VaultStatus::Active);
+
+    // Owner spends half of the pot; the uncle still gets back all he put in
+    client.withdraw(&vault_id, &token, &owner, &2_000);
+    client.close_vault(&vault_id);
+    assert_eq!(token_client.balance(&uncle), 1_000);
+    assert_eq!(token_client.balance(&owner), 3_000);
+}
+
+// ---------------------------------------------------------------------------
+// 28. test_withdrawal_allowlist_timelocked
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_withdrawal_allowlist_timelocked() {
+    let (env, _admin, client) = setup_env();
+
+    let owner = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let (token, token_client, token_admin_client) = create_token(&env, &token_admin);
+    let vault_id = client.create_vault(&owner, &token);
+    token_admin_client.mint(&owner, &1_000);
+    client.deposit(&vault_id, &token, &owner, &1_000);
+
+    let savings = Address::generate(&env);
+    let thief = Address::generate(&env);
+
+    // A new destination is only usable once its timelock has passed
+    let active_from = client.add_withdrawal_address(&vault_id, &savings);
+    let result = client.try_withdraw(&vault_id, &token, &savings, &100);
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::DestinationNotAllowed);
+    let result = client.try_withdraw(&vault_id, &token, &thief, &100);
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::DestinationNotAllowed);
+
+    // The owner's own address is always allowed
+    client.withdraw(&vault_id, &token, &owner, &100);
+
+    env.ledger().set_timestamp(active_from);
+    client.withdraw(&vault_id, &token, &savings, &200);
+    assert_eq!(token_client.balance(&savings), 200);
+
+    // Removing the last entry cannot lift the allowlist before the timelock
+    let removed_at = client.remove_withdrawal_address(&vault_id, &savings);
+    assert_eq!(removed_at, active_from + 2 * 24 * 60 * 60);
+    let result = client.try_withdraw(&vault_id, &token, &thief, &100);
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::DestinationNotAllowed);
+    assert_eq!(client.get_withdrawal_addresses(&vault_id).len(), 1);
+
+    env.ledger().set_timestamp(removed_at);
+    assert_eq!(client.get_withdrawal_addresses(&vault_id).len(), 0);
+    client.withdraw(&vault_id, &token, &thief, &100);
+}
+
+// ---------------------------------------------------------------------------
+// 29. test_withdrawal_limit_in_alert
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_withdrawal_limit_in_alert() {
+    let (env, admin, client) = setup_env();
+
+    let owner = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let (token, token_client, token_admin_client) = create_token(&env, &token_admin);
+    let vault_id = client.create_vault(&owner, &token);
+    token_admin_client.mint(&owner, &10_000);
+    client.deposit(&vault_id, &token, &owner, &10_000);
+
+    // Alert cap must not exceed the daily limit
+    let bad = WithdrawalLimit { daily_limit: 500, alert_limit: 1_000 };
+    let result = client.try_set_withdrawal_limit(&vault_id, &token, &Some(bad));
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::InvalidLimit);
+    client.set_withdrawal_limit(&vault_id, &token, &Some(WithdrawalLimit { daily_limit: 1_000, alert_limit: 300 }));
+
+    // A cap that opens withdrawals in Alert waits out the delay
+    assert!(client.get_withdrawal_limit(&vault_id, &token).is_none());
+    let day = 24 * 60 * 60;
+    env.ledger().set_timestamp(2 * day);
+    assert_eq!(client.get_withdrawal_limit(&vault_id, &token).unwrap().alert_limit, 300);
+
+    client.withdraw(&vault_id, &token, &owner, &800);
+    let result = client.try_withdraw(&vault_id, &token, &owner, &300);
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::LimitExceeded);
+
+    // The window rolls after 24h; Alert allows only the reduced cap
+    env.ledger().set_timestamp(3 * day);
+    client.transition_status(&vault_id, &admin, &VaultStatus::Alert);
+    client.withdraw(&vault_id, &token, &owner, &250);
+    let result = client.try_withdraw(&vault_id, &token, &owner, &100);
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::LimitExceeded);
+    assert_eq!(token_client.balance(&owner), 1_050);
+
+    // Lifting the cap is queued, and closing the vault still counts against it
+    client.transition_status(&vault_id, &admin, &VaultStatus::Active);
+    client.set_withdrawal_limit(&vault_id, &token, &None);
+    assert!(client.get_withdrawal_limit(&vault_id, &token).is_some());
+    assert!(client.get_pending_withdrawal_limit(&vault_id, &token).is_some());
+    let result = client.try_close_vault(&vault_id);
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::LimitExceeded);
+
+    // Lowering it applies at once and replaces the queued removal
+    client.set_withdrawal_limit(&vault_id, &token, &Some(WithdrawalLimit { daily_limit: 500, alert_limit: 0 }));
+    assert_eq!(client.get_withdrawal_limit(&vault_id, &token).unwrap().daily_limit, 500);
+    assert!(client.get_pending_withdrawal_limit(&vault_id, &token).is_none());
+
+    // Once a removal has waited out the delay, Alert blocks withdrawals entirely
+    client.set_withdrawal_limit(&vault_id, &token, &None);
+    env.ledger().set_timestamp(5 * day);
+    assert!(client.get_withdrawal_limit(&vault_id, &token).is_none());
+    client.transition_status(&vault_id, &admin, &VaultStatus::Alert);
+    let result = client.try_withdraw(&vault_id, &token, &owner, &1);
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::InvalidStatus);
+}
+
+// ---------------------------------------------------------------------------
+// 30. test_freeze_expires
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_freeze_expires() {
+    let (env, admin, client) = setup_env();
+
+    let owner = Address::generate(&env);
+    let heir = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let (token, token_client, token_admin_client) = create_token(&env, &token_admin);
+    let vault_id = client.create_vault(&owner, &token);
+    token_admin_client.mint(&owner, &1_000);
+    client.deposit(&vault_id, &token, &owner, &1_000);
+    client.set_beneficiaries(&vault_id, &single_beneficiary(&env, &heir));
+    client.transition_status(&vault_id, &admin, &VaultStatus::Triggered);
+
+    // Freezes are capped at 30 days
+    let month = 30 * 24 * 60 * 60;
+    assert!(client.try_freeze_vault(&vault_id, &(month + 1)).is_err());
+
+    // A disputed death report holds up claims while frozen
+    let until = client.freeze_vault(&vault_id, &month);
+    assert_eq!(client.get_frozen_until(&vault_id), Some(until));
+    let result = client.try_claim(&vault_id, &heir);
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::VaultFrozen);
+
+    // ...but the freeze lapses on its own and cannot be renewed right away
+    env.ledger().set_timestamp(until);
+    assert_eq!(client.get_frozen_until(&vault_id), None);
+    let result = client.try_freeze_vault(&vault_id, &month);
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::FreezeNotAllowed);
+
+    client.claim(&vault_id, &heir);
+    assert_eq!(token_client.balance(&heir), 1_000);
+}
+
+// ---------------------------------------------------------------------------
+// 31. test_dispute_restores_vault
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_dispute_restores_vault() {
+    let (env, admin, client) = setup_env();
+
+    let owner = Address::generate(&env);
+    let heir = Address::generate(&env);
+    let witness = Address::generate(&env);
+    let resolver = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let (token, token_client, token_admin_client) = create_token(&env, &token_admin);
+    let vault_id = client.create_vault(&owner, &token);
+    token_admin_client.mint(&owner, &1_000);
+    client.deposit(&vault_id, &token, &owner, &1_000);
+    client.set_beneficiaries(&vault_id, &single_beneficiary(&env, &heir));
+
+    let week = 7 * 24 * 60 * 60;
+    let mut witnesses = Vec::new(&env);
+    witnesses.push_back(witness.clone());
+    client.set_dispute_config(&vault_id, &Some(DisputeConfig { window_secs: week, resolver: resolver.clone(), witnesses }));
+
+    // ── Trigger: claims wait out the window ─────────────────────────────
+    env.ledger().set_timestamp(1_000);
+    client.transition_status(&vault_id, &admin, &VaultStatus::Triggered);
+    let result = client.try_claim(&vault_id, &heir);
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::DisputeWindowOpen);
+
+    // Strangers cannot dispute; the owner turning up alive can
+    let stranger = Address::generate(&env);
+    let result = client.try_open_dispute(&vault_id, &stranger, &BytesN::from_array(&env, &[1u8; 32]));
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::NotAuthorized);
+    client.open_dispute(&vault_id, &owner, &BytesN::from_array(&env, &[2u8; 32]));
+    client.submit_evidence(&vault_id, &witness, &BytesN::from_array(&env, &[3u8; 32]));
+
+    // An open dispute blocks claims even after the window
+    env.ledger().set_timestamp(1_000 + week);
+    let result = client.try_claim(&vault_id, &heir);
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::DisputeOpen);
+
+    client.resolve_dispute(&vault_id, &DisputeRuling::RestoreActive);
+    assert_eq!(client.get_status(&vault_id), VaultStatus::Active);
+    let disputes = client.get_disputes(&vault_id);
+    assert_eq!(disputes.len(), 1);
+    assert_eq!(disputes.get(0).unwrap().evidence.len(), 2);
+    assert_eq!(disputes.get(0).unwrap().status, DisputeStatus::Restored);
+
+    // ── A later trigger confirmed by the resolver opens claims early ────
+    env.ledger().set_timestamp(10 * week);
+    client.transition_status(&vault_id, &admin, &VaultStatus::Triggered);
+    client.open_dispute(&vault_id, &witness, &BytesN::from_array(&env, &[4u8; 32]));
+    client.resolve_dispute(&vault_id, &DisputeRuling::ConfirmTrigger);
+    let result = client.try_open_dispute(&vault_id, &owner, &BytesN::from_array(&env, &[5u8; 32]));
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::DisputeWindowClosed);
+
+    client.claim(&vault_id, &heir);
+    assert_eq!(token_client.balance(&heir), 1_000);
+}
+
+// ---------------------------------------------------------------------------
+// 32. test_platform_fees_snapshotted
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_platform_fees_snapshotted() {
+    let (env, admin, client) = setup_env();
+
+    let owner = Address::generate(&env);
+    let heir = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let (token, token_client, token_admin_client) = create_token(&env, &token_admin);
+    token_admin_client.mint(&owner, &20_000);
+
+    // Fees are capped
+    let result = client.try_set_fee_config(&FeeConfig { deposit_bps: 5_000, claim_bps: 0 });
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::FeeTooHigh);
+    client.set_fee_config(&FeeConfig { deposit_bps: 100, claim_bps: 200 });
+
+    let vault_id = client.create_vault(&owner, &token);
+    client.set_beneficiaries(&vault_id, &single_beneficiary(&env, &heir));
+
+    // Raising fees later does not touch the existing estate
+    client.set_fee_config(&FeeConfig { deposit_bps: 300, claim_bps: 300 });
+    assert_eq!(client.get_vault_fees(&vault_id), FeeConfig { deposit_bps: 100, claim_bps: 200 });
+
+    // 1% of the deposit goes to the treasury
+    client.deposit(&vault_id, &token, &owner, &10_000);
+    assert_eq!(client.get_balance(&vault_id, &token), 9_900);
+    assert_eq!(client.get_treasury_balance(&token), 100);
+
+    // 2% of the claim goes to the treasury
+    client.transition_status(&vault_id, &admin, &VaultStatus::Triggered);
+    assert_eq!(client.preview_distribution(&vault_id).get(0).unwrap().amount, 9_702);
+    client.claim(&vault_id, &heir);
+    assert_eq!(token_client.balance(&heir), 9_702);
+    assert_eq!(client.get_treasury_balance(&token), 298);
+
+    assert_eq!(client.collect_fees(&token, &treasury), 298);
+    assert_eq!(token_client.balance(&treasury), 298);
+    assert_eq!(client.get_treasury_balance(&token), 0);
+    assert!(client.try_collect_fees(&token, &treasury).is_err());
+}
+
+// ---------------------------------------------------------------------------
+// 33. test_living_allowance
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_living_allowance() {
+    let (env, admin, client) = setup_env();
+
+    let owner = Address::generate(&env);
+    let child = Address::generate(&env);
+    let heir = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let (token, token_client, token_admin_client) = create_token(&env, &token_admin);
+    let vault_id = client.create_vault(&owner, &token);
+    token_admin_client.mint(&owner, &10_000);
+    client.deposit(&vault_id, &token, &owner, &10_000);
+    client.set_beneficiaries(&vault_id, &single_beneficiary(&env, &heir));
+
+    let month = 30 * 24 * 60 * 60;
+    client.set_allowance(&vault_id, &child, &token, &500, &month, &(12 * month));
+
+    // No auth needed to push a due payment, but only once per period
+    assert_eq!(client.execute_allowance(&vault_id, &child, &token), 500);
+    let result = client.try_execute_allowance(&vault_id, &child, &token);
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::NotDue);
+
+    env.ledger().set_timestamp(month);
+    client.execute_allowance(&vault_id, &child, &token);
+    assert_eq!(token_client.balance(&child), 1_000);
+    assert_eq!(client.get_balance(&vault_id, &token), 9_000);
+
+    // Paused outside Active...
+    env.ledger().set_timestamp(2 * month);
+    client.transition_status(&vault_id, &admin, &VaultStatus::Alert);
+    let result = client.try_execute_allowance(&vault_id, &child, &token);
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::InvalidStatus);
+
+    // ...and gone once the vault triggers; the heir inherits what is left
+    client.transition_status(&vault_id, &admin, &VaultStatus::Triggered);
+    assert_eq!(client.get_allowances(&vault_id).len(), 0);
+    client.claim(&vault_id, &heir);
+    assert_eq!(token_client.balance(&heir), 9_000);
+}
+
+// ---------------------------------------------------------------------------
+// 34. test_joint_vault_survivorship
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_joint_vault_survivorship() {
+    let (env, admin, client) = setup_env();
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let heir = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let (token, token_client, token_admin_client) = create_token(&env, &token_admin);
+    let vault_id = client.create_vault(&alice, &token);
+    token_admin_client.mint(&alice, &1_000);
+    client.deposit(&vault_id, &token, &alice, &1_000);
+    client.set_beneficiaries(&vault_id, &single_beneficiary(&env, &heir));
+
+    client.add_joint_owner(&vault_id, &bob);
+    assert_eq!(client.get_owners(&vault_id), Vec::from_array(&env, [alice.clone(), bob.clone()]));
+
+    // A co-owner deposits into the shared vault like the primary owner
+    token_admin_client.mint(&bob, &500);
+    client.deposit(&vault_id, &token, &bob, &500);
+    let result = client.try_add_joint_owner(&vault_id, &Address::generate(&env));
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::JointOwnerExists);
+
+    // A joint vault cannot be triggered over the head of a living owner
+    let result = client.try_transition_status(&vault_id, &admin, &VaultStatus::Triggered);
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::SurvivorAlive);
+
+    // The vault follows the most alive owner
+    client.report_owner_status(&vault_id, &admin, &alice, &VaultStatus::GracePeriod);
+    assert_eq!(client.get_vault(&vault_id).status, VaultStatus::Active);
+    client.report_owner_status(&vault_id, &admin, &bob, &VaultStatus::Alert);
+    assert_eq!(client.get_vault(&vault_id).status, VaultStatus::Alert);
+
+    // Alice dies: Bob survives as sole owner and nothing is paid out
+    client.report_owner_status(&vault_id, &admin, &alice, &VaultStatus::Triggered);
+    let vault = client.get_vault(&vault_id);
+    assert_eq!(vault.owner, bob);
+    assert_eq!(vault.status, VaultStatus::Alert);
+    assert_eq!(client.get_owners(&vault_id), Vec::from_array(&env, [bob.clone()]));
+    let result = client.try_claim(&vault_id, &heir);
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::InvalidStatus);
+
+    client.report_owner_status(&vault_id, &admin, &bob, &VaultStatus::Active);
+    assert_eq!(client.get_vault(&vault_id).status, VaultStatus::Active);
+
+    // Bob dies: the vault triggers and the heir inherits
+    client.report_owner_status(&vault_id, &admin, &bob, &VaultStatus::Triggered);
+    assert_eq!(client.get_vault(&vault_id).status, VaultStatus::Triggered);
+    client.claim(&vault_id, &heir);
+    assert_eq!(token_client.balance(&heir), 1_500);
+}
+
+// ---------------------------------------------------------------------------
+// 35. test_strategy_invest_and_recall
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_strategy_invest_and_recall() {
+    let (env, admin, client) = setup_env();
+
+    let owner = Address::generate(&env);
+    let heir = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let (token, token_client, token_admin_client) = create_token(&env, &token_admin);
+    let vault_id = client.create_vault(&owner, &token);
+    token_admin_client.mint(&owner, &1_000);
+    client.deposit(&vault_id, &token, &owner, &1_000);
+    client.set_beneficiaries(&vault_id, &single_beneficiary(&env, &heir));
+
+    let result = client.try_invest(&vault_id, &token, &100);
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::StrategyNotSet);
+
+    let strategy_id = env.register(MockStrategy, ());
+    let strategy = MockStrategyClient::new(&env, &strategy_id);
+    client.set_strategy(&vault_id, &Some(strategy_id.clone()));
+
+    // Invested funds leave the contract but still count toward the balance
+    client.invest(&vault_id, &token, &800);
+    assert_eq!(token_client.balance(&strategy_id), 800);
+    assert_eq!(client.get_invested(&vault_id, &token), 800);
+    assert_eq!(client.get_balance(&vault_id, &token), 1_000);
+    let result = client.try_invest(&vault_id, &token, &300);
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::InsufficientBalance);
+    let result = client.try_set_strategy(&vault_id, &None);
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::StrategyInUse);
+
+    // A withdrawal beyond the idle funds recalls the position with its yield
+    token_admin_client.mint(&strategy_id, &50);
+    strategy.accrue(&client.address, &vault_id, &token, &50);
+    client.withdraw(&vault_id, &token, &owner, &500);
+    assert_eq!(token_client.balance(&owner), 500);
+    assert_eq!(client.get_invested(&vault_id, &token), 0);
+    assert_eq!(client.get_balance(&vault_id, &token), 550);
+
+    // An allowance due while everything is invested recalls the position too
+    let child = Address::generate(&env);
+    client.invest(&vault_id, &token, &550);
+    client.set_allowance(&vault_id, &child, &token, &50, &86_400, &(10 * 86_400));
+    client.execute_allowance(&vault_id, &child, &token);
+    assert_eq!(token_client.balance(&child), 50);
+    assert_eq!(client.get_invested(&vault_id, &token), 0);
+
+    // A failing strategy does not block the trigger, but claims wait for the
+    // funds instead of paying out money the contract does not hold
+    client.invest(&vault_id, &token, &500);
+    strategy.set_failing(&true);
+    client.transition_status(&vault_id, &admin, &VaultStatus::Triggered);
+    assert_eq!(client.get_invested(&vault_id, &token), 500);
+    let result = client.try_divest(&vault_id, &token);
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::StrategyFailed);
+    let result = client.try_claim(&vault_id, &heir);
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::StrategyFailed);
+
+    strategy.set_failing(&false);
+    client.claim(&vault_id, &heir);
+    assert_eq!(token_client.balance(&heir), 500);
+    assert_eq!(client.get_invested(&vault_id, &token), 0);
+}
+
+// ---------------------------------------------------------------------------
+// 36. test_balances_reconcile_across_flows
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_balances_reconcile_across_flows() {
+    let (env, admin, client) = setup_env();
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let heir = Address::generate(&env);
+    let child = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let (token, _, token_admin_client) = create_token(&env, &token_admin);
+    client.set_fee_config(&FeeConfig { deposit_bps: 100, claim_bps: 200 });
+
+    let vault_a = client.create_vault(&alice, &token);
+    let vault_b = client.create_vault(&bob, &token);
+    token_admin_client.mint(&alice, &5_000);
+    token_admin_client.mint(&bob, &3_000);
+    client.deposit(&vault_a, &token, &alice, &5_000);
+    client.deposit(&vault_b, &token, &bob, &3_000);
+    assert_reconciled(&client, &token);
+
+    client.withdraw(&vault_a, &token, &alice, &1_000);
+    client.set_allowance(&vault_b, &child, &token, &250, &86_400, &(10 * 86_400));
+    client.execute_allowance(&vault_b, &child, &token);
+    assert_reconciled(&client, &token);
+
+    // Strategy positions and their yield stay on the books
+    let strategy_id = env.register(MockStrategy, ());
+    let strategy = MockStrategyClient::new(&env, &strategy_id);
+    client.set_strategy(&vault_a, &Some(strategy_id.clone()));
+    client.invest(&vault_a, &token, &2_000);
+    assert_reconciled(&client, &token);
+    token_admin_client.mint(&strategy_id, &100);
+    strategy.accrue(&client.address, &vault_a, &token, &100);
+    client.divest(&vault_a, &token);
+    assert_reconciled(&client, &token);
+
+    client.set_beneficiaries(&vault_a, &single_beneficiary(&env, &heir));
+    client.transition_status(&vault_a, &admin, &VaultStatus::Triggered);
+    client.claim(&vault_a, &heir);
+    client.close_vault(&vault_b);
+    assert_reconciled(&client, &token);
+
+    client.collect_fees(&token, &admin);
+    assert_reconciled(&client, &token);
+}
+
+// ---------------------------------------------------------------------------
+// 37. test_sweep_direct_transfer
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_sweep_direct_transfer() {
+    let (env, _, client) = setup_env();
+
+    let owner = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let (token, _, token_admin_client) = create_token(&env, &token_admin);
+    let (other, _, other_admin_client) = create_token(&env, &token_admin);
+    let vault_id = client.create_vault(&owner, &token);
+    token_admin_client.mint(&owner, &1_000);
+    client.deposit(&vault_id, &token, &owner, &1_000);
+
+    let result = client.try_sweep(&token, &Some(vault_id));
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::NoSurplus);
+
+    // Tokens sent straight to the contract are invisible until swept
+    token_admin_client.mint(&client.address, &300);
+    let report = client.sync_balance(&token);
+    assert_eq!(report.actual, 1_300);
+    assert_eq!(report.tracked, 1_000);
+    assert_eq!(report.surplus, 300);
+    assert_eq!(client.get_balance(&vault_id, &token), 1_000);
+
+    assert_eq!(client.sweep(&token, &Some(vault_id)), 300);
+    assert_eq!(client.get_balance(&vault_id, &token), 1_300);
+    assert_reconciled(&client, &token);
+
+    // A token the vault never held is added to it; or the surplus goes to the treasury
+    other_admin_client.mint(&client.address, &50);
+    client.sweep(&other, &Some(vault_id));
+    assert_eq!(client.get_balance(&vault_id, &other), 50);
+    other_admin_client.mint(&client.address, &20);
+    client.sweep(&other, &None);
+    assert_eq!(client.get_treasury_balance(&other), 20);
+    assert_reconciled(&client, &other);
+}
+
+// ---------------------------------------------------------------------------
+// 38. test_owner_index_and_listing
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_owner_index_and_listing() {
+    let (env, admin, client) = setup_env();
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let (token, _, _) = create_token(&env, &token_admin);
+
+    let first = client.create_vault(&alice, &token);
+    let second = client.create_vault(&bob, &token);
+    let third = client.create_vault(&alice, &token);
+    assert_eq!(client.get_vaults_by_owner(&alice), Vec::from_array(&env, [first, third]));
+    assert_eq!(client.get_vaults_by_owner(&bob), Vec::from_array(&env, [second]));
+
+    // A co-owner is indexed too, and a deceased owner drops out
+    client.add_joint_owner(&second, &alice);
+    assert_eq!(client.get_vaults_by_owner(&alice), Vec::from_array(&env, [first, third, second]));
+    client.report_owner_status(&second, &admin, &bob, &VaultStatus::Triggered);
+    assert_eq!(client.get_vaults_by_owner(&bob).len(), 0);
+    assert_eq!(client.get_vault(&second).owner, alice);
+
+    // Pages scan a fixed ID range; the filter may leave them short
+    client.transition_status(&third, &admin, &VaultStatus::Alert);
+    assert_eq!(client.get_vault_count(), 3);
+    let page = client.list_vaults(&0, &2, &None);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get(1).unwrap().id, second);
+    assert_eq!(client.list_vaults(&2, &2, &None).len(), 1);
+    assert_eq!(client.list_vaults(&5, &2, &None).len(), 0);
+
+    let alerts = client.list_vaults(&0, &10, &Some(VaultStatus::Alert));
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts.get(0).unwrap().id, third);
+    assert_eq!(client.list_vaults(&0, &2, &Some(VaultStatus::Alert)).len(), 0);
+}
+
+// ---------------------------------------------------------------------------
+// 39. test_priority_tiers_cannot_block_heirs
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_priority_tiers_cannot_block_heirs() {
+    let (env, admin, client) = setup_env();
+
+    let owner = Address::generate(&env);
+    let executor = Address::generate(&env);
+    let lost_creditor = Address::generate(&env);
+    let heir = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let (token, token_client, token_admin_client) = create_token(&env, &token_admin);
+    let vault_id = client.create_vault(&owner, &token);
+    token_admin_client.mint(&owner, &10_000);
+    client.deposit(&vault_id, &token, &owner, &10_000);
+    client.set_beneficiaries(&vault_id, &single_beneficiary(&env, &heir));
+    client.set_executor(&vault_id, &executor, &100);
+    let mut creditors = Vec::new(&env);
+    creditors.push_back(CreditorClaim { creditor: lost_creditor.clone(), token: token.clone(), amount: 1, paid: false });
+    client.set_creditors(&vault_id, &creditors);
+    env.ledger().set_timestamp(7 * 24 * 60 * 60);
+    client.execute_pending_waterfall(&vault_id);
+
+    client.transition_status(&vault_id, &admin, &VaultStatus::Triggered);
+
+    // Anyone may push a priority payment; it still goes to the recorded address
+    env.set_auths(&[]);
+    client.claim_executor_fee(&vault_id);
+    assert_eq!(token_client.balance(&executor), 100);
+    env.mock_all_auths();
+
+    // A creditor that never acts only holds the heirs up until the claim window ends
+    let result = client.try_claim(&vault_id, &heir);
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::PriorTierPending);
+    env.ledger().set_timestamp((7 + 180) * 24 * 60 * 60);
+    let result = client.try_claim_debt(&vault_id, &lost_creditor);
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::PriorityClaimLapsed);
+
+    client.claim(&vault_id, &heir);
+    assert_eq!(token_client.balance(&heir), 9_900);
+    assert_eq!(client.get_vault(&vault_id).status, VaultStatus::Distributed);
+}
+
+// ---------------------------------------------------------------------------
+// 40. test_waterfall_changes_timelocked
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_waterfall_changes_timelocked() {
+    let (env, admin, client) = setup_env();
+
+    let owner = Address::generate(&env);
+    let heir = Address::generate(&env);
+    let guardian = Address::generate(&env);
+    let coercer = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let (token, _, token_admin_client) = create_token(&env, &token_admin);
+    let vault_id = client.create_vault(&owner, &token);
+    token_admin_client.mint(&owner, &1_000);
+    client.deposit(&vault_id, &token, &owner, &1_000);
+    client.set_beneficiaries(&vault_id, &single_beneficiary(&env, &heir));
+    client.set_guardians(&vault_id, &Vec::from_array(&env, [guardian.clone()]));
+
+    // Leaving the whole estate to someone else is queued, and a guardian can cancel it
+    let mut bequests = Vec::new(&env);
+    bequests.push_back(Bequest { recipient: coercer.clone(), token: token.clone(), kind: BequestKind::WholeToken, amount: 0, paid: false });
+    client.set_bequests(&vault_id, &bequests);
+    assert_eq!(client.get_bequests(&vault_id).len(), 0);
+    assert_eq!(client.get_pending_waterfall(&vault_id).len(), 1);
+    client.cancel_pending_waterfall(&vault_id, &guardian);
+    assert_eq!(client.get_pending_waterfall(&vault_id).len(), 0);
+
+    // Re-queued, it only applies once the delay has passed
+    client.set_bequests(&vault_id, &bequests);
+    let result = client.try_execute_pending_waterfall(&vault_id);
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::TimelockActive);
+    env.ledger().set_timestamp(7 * 24 * 60 * 60);
+    client.execute_pending_waterfall(&vault_id);
+    assert_eq!(client.get_bequests(&vault_id).len(), 1);
+
+    // Naming an executor is queued too; a second bequest change replaces the first
+    client.set_executor(&vault_id, &coercer, &500);
+    assert!(client.get_executor(&vault_id).is_none());
+    client.set_bequests(&vault_id, &bequests);
+    client.set_bequests(&vault_id, &Vec::new(&env));
+    assert_eq!(client.get_pending_waterfall(&vault_id).len(), 2);
+
+    // A change still queued when the vault leaves Active is dropped
+    client.transition_status(&vault_id, &admin, &VaultStatus::Alert);
+    assert_eq!(client.get_pending_waterfall(&vault_id).len(), 0);
+}
+
+// ---------------------------------------------------------------------------
+// 41. test_recall_books_only_tokens_received
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_recall_books_only_tokens_received() {
+    let (env, _admin, client) = setup_env();
+
+    let victim = Address::generate(&env);
+    let attacker = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let (token, token_client, token_admin_client) = create_token(&env, &token_admin);
+    let victim_vault = client.create_vault(&victim, &token);
+    let attacker_vault = client.create_vault(&attacker, &token);
+    token_admin_client.mint(&victim, &1_000);
+    token_admin_client.mint(&attacker, &10);
+    client.deposit(&victim_vault, &token, &victim, &1_000);
+    client.deposit(&attacker_vault, &token, &attacker, &10);
+
+    // A strategy that reports a huge position but sends nothing back
+    let strategy_id = env.register(MockStrategy, ());
+    let strategy = MockStrategyClient::new(&env, &strategy_id);
+    client.set_strategy(&attacker_vault, &Some(strategy_id.clone()));
+    client.invest(&attacker_vault, &token, &1);
+    strategy.accrue(&client.address, &attacker_vault, &token, &1_000_000);
+    strategy.set_lying(&true);
+
+    let result = client.try_divest(&attacker_vault, &token);
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::StrategyFailed);
+    assert_eq!(client.get_balance(&attacker_vault, &token), 10);
+    let result = client.try_withdraw(&attacker_vault, &token, &attacker, &1_000);
+    assert!(result.is_err());
+    assert_eq!(token_client.balance(&client.address), 1_009);
+    assert_eq!(client.get_balance(&victim_vault, &token), 1_000);
+}
+
+// ---------------------------------------------------------------------------
+// 42. test_guardian_removal_timelocked
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_guardian_removal_timelocked() {
+    let (env, _admin, client) = setup_env();
+
+    let owner = Address::generate(&env);
+    let token = Address::generate(&env);
+    let vault_id = client.create_vault(&owner, &token);
+
+    let heir = Address::generate(&env);
+    let coercer = Address::generate(&env);
+    let guardian = Address::generate(&env);
+    client.set_beneficiaries(&vault_id, &single_beneficiary(&env, &heir));
+    client.set_guardians(&vault_id, &Vec::from_array(&env, [guardian.clone()]));
+    assert_eq!(client.get_guardians(&vault_id).len(), 1);
+
+    // Dropping the guardian is queued, so they can still cancel the swap
+    client.set_guardians(&vault_id, &Vec::new(&env));
+    client.set_beneficiaries(&vault_id, &single_beneficiary(&env, &coercer));
+    assert_eq!(client.get_guardians(&vault_id).len(), 1);
+    client.cancel_pending_beneficiaries(&vault_id, &guardian);
+    client.cancel_pending_guardians(&vault_id, &guardian);
+    assert!(client.get_pending_guardians(&vault_id).is_none());
+
+    // An uncontested removal applies once the delay has passed
+    client.set_guardians(&vault_id, &Vec::new(&env));
+    let result = client.try_execute_pending_guardians(&vault_id);
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::TimelockActive);
+    env.ledger().set_timestamp(client.get_beneficiary_delay());
+    client.execute_pending_guardians(&vault_id);
+    assert_eq!(client.get_guardians(&vault_id).len(), 0);
+
+    // The admin cannot switch the timelock off
+    let result = client.try_set_beneficiary_delay(&0);
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::InvalidLimit);
+    client.set_beneficiary_delay(&(24 * 60 * 60));
+}
+
+// ---------------------------------------------------------------------------
+// 43. test_unresolved_dispute_lapses
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_unresolved_dispute_lapses() {
+    let (env, admin, client) = setup_env();
+
+    let owner = Address::generate(&env);
+    let heir = Address::generate(&env);
+    let witness = Address::generate(&env);
+    let resolver = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let (token, token_client, token_admin_client) = create_token(&env, &token_admin);
+    let vault_id = client.create_vault(&owner, &token);
+    token_admin_client.mint(&owner, &1_000);
+    client.deposit(&vault_id, &token, &owner, &1_000);
+    client.set_beneficiaries(&vault_id, &single_beneficiary(&env, &heir));
+
+    let day = 24 * 60 * 60;
+    let witnesses = Vec::from_array(&env, [witness.clone()]);
+    client.set_dispute_config(&vault_id, &Some(DisputeConfig { window_secs: 7 * day, resolver, witnesses }));
+
+    env.ledger().set_timestamp(1_000);
+    client.transition_status(&vault_id, &admin, &VaultStatus::Triggered);
+    client.open_dispute(&vault_id, &witness, &BytesN::from_array(&env, &[1u8; 32]));
+
+    // A silent resolver cannot hold the estate hostage: after the deadline
+    // the trigger stands and the dispute can no longer be ruled on
+    env.ledger().set_timestamp(1_000 + 29 * day);
+    let result = client.try_claim(&vault_id, &heir);
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::DisputeOpen);
+    env.ledger().set_timestamp(1_000 + 30 * day);
+    let result = client.try_resolve_dispute(&vault_id, &DisputeRuling::RestoreActive);
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::DisputeWindowClosed);
+    let result = client.try_submit_evidence(&vault_id, &witness, &BytesN::from_array(&env, &[2u8; 32]));
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::DisputeWindowClosed);
+    client.claim(&vault_id, &heir);
+    assert_eq!(token_client.balance(&heir), 1_000);
+
+    // Restoring is only possible while the vault is still Triggered
+    let other = client.create_vault(&owner, &token);
+    let witnesses = Vec::from_array(&env, [witness.clone()]);
+    client.set_dispute_config(&other, &Some(DisputeConfig { window_secs: 7 * day, resolver: Address::generate(&env), witnesses }));
+    client.transition_status(&other, &admin, &VaultStatus::Triggered);
+    client.open_dispute(&other, &witness, &BytesN::from_array(&env, &[3u8; 32]));
+    client.transition_status(&other, &admin, &VaultStatus::Alert);
+    let result = client.try_resolve_dispute(&other, &DisputeRuling::RestoreActive);
+    assert_eq!(result.err().unwrap().unwrap(), VaultError::InvalidStatus);
+    assert_eq!(client.get_status(&other), VaultStatus::Alert);
+}
@@ -1,4 +1,4 @@
-use soroban_sdk::{contracttype, Address};
+use soroban_sdk::{contracttype, Address, BytesN, Vec};
 
 pub type VaultId = u64;
 
@@ -10,6 +10,8 @@ pub enum VaultStatus {
     GracePeriod,
     Triggered,
     Distributed,
+    /// Retired by the owner; terminal.
+    Closed,
 }
 
 #[contracttype]
@@ -20,6 +22,241 @@ pub struct Beneficiary {
     pub claimed: bool,
 }
 
+/// A hidden beneficiary: `commitment` is `sha256(address_xdr || salt)`.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct BeneficiaryCommitment {
+    pub commitment: BytesN<32>,
+    pub percentage: u32,
+    pub claimed: bool,
+}
+
+/// A beneficiary change waiting out its timelock. Exactly one list is non-empty.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct PendingBeneficiaries {
+    pub beneficiaries: Vec<Beneficiary>,
+    pub commitments: Vec<BeneficiaryCommitment>,
+    pub queued_at: u64,
+    pub executable_at: u64,
+}
+
+/// A guardian change that drops at least one guardian, waiting out the
+/// beneficiary delay so the outgoing guardians can still cancel it.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct PendingGuardians {
+    pub guardians: Vec<Address>,
+    pub queued_at: u64,
+    pub executable_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum BequestKind {
+    /// A fixed `amount` of the token.
+    Fixed,
+    /// Everything left of the token once earlier bequests are paid.
+    WholeToken,
+}
+
+/// A specific-asset bequest, paid before the percentage residue. `amount` is
+/// the configured amount for `Fixed` (0 for `WholeToken`) and becomes the
+/// allocated amount once the vault is settled at the first claim.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct Bequest {
+    pub recipient: Address,
+    pub token: Address,
+    pub kind: BequestKind,
+    pub amount: i128,
+    pub paid: bool,
+}
+
+/// Executor fee, paid ahead of every other tier as `fee_bps` of each token
+/// balance at the time the executor claims.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct ExecutorFee {
+    pub executor: Address,
+    pub fee_bps: u32,
+    pub paid: bool,
+}
+
+/// A fixed-amount debt, paid after the executor and before any heir. Creditors
+/// are paid in list order; a released claim is marked paid with amount 0.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct CreditorClaim {
+    pub creditor: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub paid: bool,
+}
+
+/// What `recipient` would receive of `token` if the waterfall ran now.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Payout {
+    pub recipient: Address,
+    pub token: Address,
+    pub amount: i128,
+}
+
+/// Who besides the owner may deposit into a vault. When `refund_on_close` is
+/// set, closing the vault refunds other contributors first, pro-rata if the
+/// vault can no longer cover what they put in.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct DepositConfig {
+    pub open: bool,
+    pub allowlist: Vec<Address>,
+    pub refund_on_close: bool,
+}
+
+/// An allowed withdrawal destination, usable from `active_from` onwards.
+/// A removal is timelocked too: the entry stays in force until `removed_at`
+/// (0 while no removal is scheduled).
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct WithdrawalAddress {
+    pub address: Address,
+    pub active_from: u64,
+    pub removed_at: u64,
+}
+
+/// Rolling 24h withdrawal caps for one token. `alert_limit` is the reduced
+/// cap that stays available while the vault is in Alert.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct WithdrawalLimit {
+    pub daily_limit: i128,
+    pub alert_limit: i128,
+}
+
+/// A raised or removed withdrawal limit waiting out the allowlist delay. A
+/// `daily_limit` of 0 removes the cap once `executable_at` has passed.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct PendingWithdrawalLimit {
+    pub daily_limit: i128,
+    pub alert_limit: i128,
+    pub executable_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct WithdrawalRecord {
+    pub timestamp: u64,
+    pub amount: i128,
+}
+
+/// Optional dispute period after trigger. Claims wait until `window_secs`
+/// have passed since the trigger; within it the owner, a guardian or a
+/// witness can open a dispute that only `resolver` can settle.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct DisputeConfig {
+    pub window_secs: u64,
+    pub resolver: Address,
+    pub witnesses: Vec<Address>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum DisputeRuling {
+    /// The owner is alive; the vault goes back to Active.
+    RestoreActive,
+    /// The trigger stands; claims may proceed.
+    ConfirmTrigger,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum DisputeStatus {
+    Open,
+    Restored,
+    Confirmed,
+}
+
+/// Hash of off-chain evidence submitted to a dispute.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct Evidence {
+    pub submitted_by: Address,
+    pub hash: BytesN<32>,
+    pub timestamp: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct Dispute {
+    pub opened_by: Address,
+    pub opened_at: u64,
+    pub evidence: Vec<Evidence>,
+    pub status: DisputeStatus,
+    pub resolved_at: Option<u64>,
+}
+
+/// Platform fees in basis points. The config in force when a vault is
+/// created is snapshotted onto it and never changes afterwards.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeeConfig {
+    pub deposit_bps: u32,
+    pub claim_bps: u32,
+}
+
+/// Recurring payout of `amount` to `recipient` every `period_secs` while the
+/// vault is Active, until `end_at`. One allowance per recipient and token.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct Allowance {
+    pub recipient: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub period_secs: u64,
+    pub next_due: u64,
+    pub end_at: u64,
+}
+
+/// The contract's actual holding of a token against what its books account
+/// for: vault balances not out in a strategy, plus the fee treasury.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct BalanceReport {
+    pub token: Address,
+    pub actual: i128,
+    pub tracked: i128,
+    pub surplus: i128,
+}
+
+/// A change to the payout waterfall, held back like a beneficiary change.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum WaterfallChange {
+    Bequests(Vec<Bequest>),
+    Executor(ExecutorFee),
+    Creditors(Vec<CreditorClaim>),
+}
+
+/// A waterfall change waiting out the beneficiary timelock. At most one is
+/// queued per kind of change.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct PendingWaterfall {
+    pub change: WaterfallChange,
+    pub queued_at: u64,
+    pub executable_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct TokenBalance {
+    pub token: Address,
+    pub amount: i128,
+}
+
 #[contracttype]
 #[derive(Clone, Debug)]
 pub struct VaultInfo {
@@ -27,7 +264,6 @@ pub struct VaultInfo {
     pub owner: Address,
     pub token: Address,
     pub status: VaultStatus,
-    pub balance: i128,
     pub created_at: u64,
     pub last_updated: u64,
 }
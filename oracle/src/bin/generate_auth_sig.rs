@@ -70,7 +70,7 @@ fn main() {
     
     // Output results
     println!("=== ED25519 Signature Generation ===\n");
-    println!("Public Key (hex): {}", hex::encode(&public_key_bytes));
+    println!("Public Key (hex): {}", hex::encode(public_key_bytes));
     println!("Stellar Address: {}\n", stellar_address);
     println!("Message: {}\n", message);
     println!("Signature (hex): {}\n", hex::encode(signature.to_bytes()));
@@ -12,6 +12,7 @@ pub enum VaultStatus {
     GracePeriod,
     Triggered,
     Distributed,
+    Closed,
 }
 
 impl VaultStatus {
@@ -22,6 +23,7 @@ impl VaultStatus {
             Self::GracePeriod => "grace_period",
             Self::Triggered => "triggered",
             Self::Distributed => "distributed",
+            Self::Closed => "closed",
         }
     }
 
@@ -32,6 +34,7 @@ impl VaultStatus {
             "grace_period" => Self::GracePeriod,
             "triggered" => Self::Triggered,
             "distributed" => Self::Distributed,
+            "closed" => Self::Closed,
             _ => Self::Active,
         }
     }
@@ -26,6 +26,7 @@ impl Asset {
         .await
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn create(
         pool: &PgPool,
         owner_id: Uuid,
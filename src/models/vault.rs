@@ -55,6 +55,7 @@ impl Vault {
     }
 
     /// Set contract ID after on-chain deployment.
+    #[allow(dead_code)]
     pub async fn set_contract_id(pool: &PgPool, id: Uuid, contract_id: &str) -> sqlx::Result<()> {
         sqlx::query(
             "UPDATE vaults SET contract_id = $1, last_synced_at = NOW() WHERE id = $2",
@@ -67,6 +68,7 @@ impl Vault {
     }
 
     /// Set escrow contract ID.
+    #[allow(dead_code)]
     pub async fn set_escrow(
         pool: &PgPool,
         id: Uuid,
@@ -16,6 +16,7 @@ pub struct Verification {
 
 impl Verification {
     /// Get verification history for a user.
+    #[allow(dead_code)]
     pub async fn find_by_user(
         pool: &PgPool,
         user_id: Uuid,